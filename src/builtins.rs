@@ -0,0 +1,310 @@
+use crate::callable::*;
+use crate::interpreter::*;
+use crate::native::{bool, list, nil, number, string, string_arg};
+use crate::token::{LiteralValue, Token};
+
+// Longest string `repeat` will build, in bytes. Anything bigger is almost
+// certainly a mistake, and would otherwise abort on allocation.
+const MAX_REPEAT_LEN: usize = 1 << 28;
+
+#[derive(Clone)]
+pub struct BoundNative {
+    pub receiver: CallableRet,
+    pub method: BuiltinMethod,
+    pub name: Token,
+}
+
+#[derive(Clone, Copy)]
+pub enum BuiltinMethod {
+    Len,
+    Upper,
+    Lower,
+    Trim,
+    Split,
+    Contains,
+    StartsWith,
+    EndsWith,
+    Substr,
+    IndexOf,
+    Replace,
+    Repeat,
+    Chars,
+    Floor,
+    Ceil,
+    Round,
+    Abs,
+    ToFixed,
+    Push,
+    Pop,
+    Map,
+    Filter,
+    Join,
+    Keys,
+    Values,
+    Has,
+    Remove,
+}
+
+impl BuiltinMethod {
+    pub fn lookup(receiver: &CallableRet, name: &str) -> Option<Self> {
+        let method = match receiver {
+            CallableRet::Value(LiteralValue::StringLiteral(_)) => match name {
+                "len" => Self::Len,
+                "upper" => Self::Upper,
+                "lower" => Self::Lower,
+                "trim" => Self::Trim,
+                "split" => Self::Split,
+                "contains" => Self::Contains,
+                "startsWith" => Self::StartsWith,
+                "endsWith" => Self::EndsWith,
+                "substr" => Self::Substr,
+                "indexOf" => Self::IndexOf,
+                "replace" => Self::Replace,
+                "repeat" => Self::Repeat,
+                "chars" => Self::Chars,
+                _ => return None,
+            },
+            CallableRet::Value(LiteralValue::NumberLiteral(_)) => match name {
+                "floor" => Self::Floor,
+                "ceil" => Self::Ceil,
+                "round" => Self::Round,
+                "abs" => Self::Abs,
+                "toFixed" => Self::ToFixed,
+                _ => return None,
+            },
+            CallableRet::List(_) => match name {
+                "len" => Self::Len,
+                "push" => Self::Push,
+                "pop" => Self::Pop,
+                "map" => Self::Map,
+                "filter" => Self::Filter,
+                "contains" => Self::Contains,
+                "join" => Self::Join,
+                _ => return None,
+            },
+            CallableRet::Map(_) => match name {
+                "len" => Self::Len,
+                "keys" => Self::Keys,
+                "values" => Self::Values,
+                "has" => Self::Has,
+                "remove" => Self::Remove,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some(method)
+    }
+
+    pub fn arity(&self) -> usize {
+        match self {
+            Self::Len
+            | Self::Upper
+            | Self::Lower
+            | Self::Trim
+            | Self::Floor
+            | Self::Ceil
+            | Self::Round
+            | Self::Abs
+            | Self::Pop
+            | Self::Keys
+            | Self::Values
+            | Self::Chars => 0,
+            Self::Split
+            | Self::Contains
+            | Self::StartsWith
+            | Self::EndsWith
+            | Self::ToFixed
+            | Self::Push
+            | Self::Map
+            | Self::Filter
+            | Self::Join
+            | Self::Has
+            | Self::Remove
+            | Self::IndexOf
+            | Self::Repeat => 1,
+            Self::Substr | Self::Replace => 2,
+        }
+    }
+}
+
+impl BoundNative {
+    pub fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: &[CallableRet],
+    ) -> Result<CallableRet, RuntimeException> {
+        let method = self.name.lexeme.as_str();
+        match &self.receiver {
+            CallableRet::Value(LiteralValue::StringLiteral(s)) => Ok(match self.method {
+                BuiltinMethod::Len => number(s.chars().count() as f64),
+                BuiltinMethod::Upper => string(s.to_uppercase()),
+                BuiltinMethod::Lower => string(s.to_lowercase()),
+                BuiltinMethod::Trim => string(String::from(s.trim())),
+                BuiltinMethod::Split => {
+                    let separator = string_arg(&arguments[0], method)?;
+                    let parts: Vec<CallableRet> = if separator.is_empty() {
+                        s.chars().map(|c| string(String::from(c))).collect()
+                    } else {
+                        s.split(separator)
+                            .map(|part| string(String::from(part)))
+                            .collect()
+                    };
+                    list(parts)
+                }
+                BuiltinMethod::Contains => bool(s.contains(string_arg(&arguments[0], method)?)),
+                BuiltinMethod::StartsWith => {
+                    bool(s.starts_with(string_arg(&arguments[0], method)?))
+                }
+                BuiltinMethod::EndsWith => bool(s.ends_with(string_arg(&arguments[0], method)?)),
+                // Indices and lengths count characters, and are clamped to the
+                // end of the string.
+                BuiltinMethod::Substr => {
+                    let start = count_arg(&arguments[0], method)?;
+                    let length = count_arg(&arguments[1], method)?;
+                    string(s.chars().skip(start).take(length).collect())
+                }
+                BuiltinMethod::IndexOf => {
+                    let needle = string_arg(&arguments[0], method)?;
+                    match s.find(needle) {
+                        Some(byte) => number(s[..byte].chars().count() as f64),
+                        None => number(-1.0),
+                    }
+                }
+                BuiltinMethod::Replace => {
+                    let from = string_arg(&arguments[0], method)?;
+                    let to = string_arg(&arguments[1], method)?;
+                    if from.is_empty() {
+                        string(s.clone())
+                    } else {
+                        string(s.replace(from, to))
+                    }
+                }
+                BuiltinMethod::Repeat => {
+                    let count = count_arg(&arguments[0], method)?;
+                    match s.len().checked_mul(count) {
+                        Some(len) if len <= MAX_REPEAT_LEN => string(s.repeat(count)),
+                        _ => {
+                            return Err(RuntimeException::InvalidArgument(String::from(
+                                "repeat() result would be too long.",
+                            )))
+                        }
+                    }
+                }
+                BuiltinMethod::Chars => list(s.chars().map(|c| string(String::from(c))).collect()),
+                _ => unreachable!(),
+            }),
+            CallableRet::Value(LiteralValue::NumberLiteral(n)) => Ok(match self.method {
+                BuiltinMethod::Floor => number(n.floor()),
+                BuiltinMethod::Ceil => number(n.ceil()),
+                BuiltinMethod::Round => number(n.round()),
+                BuiltinMethod::Abs => number(n.abs()),
+                BuiltinMethod::ToFixed => match &arguments[0] {
+                    CallableRet::Value(LiteralValue::NumberLiteral(digits))
+                        if digits.fract() == 0.0 && (0.0..=100.0).contains(digits) =>
+                    {
+                        string(format!("{:.*}", *digits as usize, n))
+                    }
+                    _ => {
+                        return Err(RuntimeException::InvalidArgument(String::from(
+                            "toFixed() digits must be an integer between 0 and 100.",
+                        )))
+                    }
+                },
+                _ => unreachable!(),
+            }),
+            CallableRet::List(elements) => match self.method {
+                BuiltinMethod::Len => Ok(number(elements.borrow().len() as f64)),
+                BuiltinMethod::Push => {
+                    elements.borrow_mut().push(arguments[0].clone());
+                    Ok(nil())
+                }
+                BuiltinMethod::Pop => elements.borrow_mut().pop().ok_or_else(|| {
+                    RuntimeException::InvalidIndex(
+                        self.name.clone(),
+                        String::from("Can't pop from an empty list."),
+                    )
+                }),
+                BuiltinMethod::Map => {
+                    let snapshot = elements.borrow().clone();
+                    let mut mapped = Vec::with_capacity(snapshot.len());
+                    for element in snapshot {
+                        mapped.push(interpreter.call_value(
+                            arguments[0].clone(),
+                            vec![element],
+                            &self.name,
+                        )?);
+                    }
+                    Ok(list(mapped))
+                }
+                BuiltinMethod::Filter => {
+                    let snapshot = elements.borrow().clone();
+                    let mut kept = Vec::new();
+                    for element in snapshot {
+                        let keep = interpreter.call_value(
+                            arguments[0].clone(),
+                            vec![element.clone()],
+                            &self.name,
+                        )?;
+                        if interpreter.is_true(&keep) {
+                            kept.push(element);
+                        }
+                    }
+                    Ok(list(kept))
+                }
+                BuiltinMethod::Contains => {
+                    let snapshot = elements.borrow().clone();
+                    for element in &snapshot {
+                        if interpreter.equals(element, &arguments[0], &self.name)? {
+                            return Ok(bool(true));
+                        }
+                    }
+                    Ok(bool(false))
+                }
+                BuiltinMethod::Join => {
+                    let separator = string_arg(&arguments[0], method)?;
+                    let parts: Vec<String> =
+                        elements.borrow().iter().map(|e| e.to_string()).collect();
+                    Ok(string(parts.join(separator)))
+                }
+                _ => unreachable!(),
+            },
+            CallableRet::Map(entries) => match self.method {
+                BuiltinMethod::Len => Ok(number(entries.borrow().len() as f64)),
+                BuiltinMethod::Keys => {
+                    Ok(list(entries.borrow().keys().cloned().map(string).collect()))
+                }
+                BuiltinMethod::Values => Ok(list(entries.borrow().values().cloned().collect())),
+                BuiltinMethod::Has => {
+                    let key = key_arg(&arguments[0])?;
+                    Ok(bool(entries.borrow().contains_key(key)))
+                }
+                BuiltinMethod::Remove => {
+                    let key = key_arg(&arguments[0])?;
+                    Ok(entries.borrow_mut().remove(key).unwrap_or_else(nil))
+                }
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        }
+    }
+}
+
+fn count_arg(argument: &CallableRet, method: &str) -> Result<usize, RuntimeException> {
+    match argument {
+        CallableRet::Value(LiteralValue::NumberLiteral(n)) if n.fract() == 0.0 && *n >= 0.0 => {
+            Ok(*n as usize)
+        }
+        _ => Err(RuntimeException::InvalidArgument(format!(
+            "Argument to {method}() must be a non-negative integer."
+        ))),
+    }
+}
+
+fn key_arg(argument: &CallableRet) -> Result<&str, RuntimeException> {
+    match argument {
+        CallableRet::Value(LiteralValue::StringLiteral(s)) => Ok(s),
+        _ => Err(RuntimeException::InvalidArgument(String::from(
+            MAP_KEYS_MUST_BE_STRINGS,
+        ))),
+    }
+}
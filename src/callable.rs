@@ -1,38 +1,101 @@
 use std::cell::RefCell;
+use std::collections::BTreeMap;
 use std::fmt::Display;
 use std::rc::Rc;
-use std::time::{SystemTime, UNIX_EPOCH};
 
+use regex::Regex;
+
+use crate::builtins::BoundNative;
+use crate::class::*;
 use crate::environment::*;
+use crate::generator::Generator;
 use crate::interpreter::*;
+use crate::native::NativeFunction;
+use crate::scheduler::*;
 use crate::statement::*;
-use crate::token::LiteralValue;
+use crate::time::LoxTime;
+use crate::token::{LiteralValue, Token};
 
 #[derive(Clone)]
 pub enum CallableRet {
     Value(LiteralValue),
     Callable(Callable),
+    Generator(Rc<RefCell<Generator>>),
+    List(Rc<RefCell<Vec<CallableRet>>>),
+    Map(Rc<RefCell<BTreeMap<String, CallableRet>>>),
+    Range(f64, f64),
+    Channel(Rc<RefCell<Channel>>),
+    Regex(Rc<Regex>),
+    Time(LoxTime),
+    Await(Await),
+    Enum(Rc<LoxEnum>),
+    EnumVariant(Rc<LoxEnum>, usize),
+    Instance(Rc<RefCell<Instance>>),
+    Trait(Rc<LoxTrait>),
 }
 
 #[derive(Clone)]
 pub enum Callable {
     Function(FunctionInner),
-    Native(Clock),
+    Native(NativeFunction),
+    GeneratorMethod(Rc<RefCell<Generator>>, GeneratorMethod),
+    ChannelMethod(Rc<RefCell<Channel>>, ChannelMethod),
+    EnumValues(Rc<LoxEnum>),
+    BoundNative(Box<BoundNative>),
+    Class(Rc<LoxClass>),
 }
 
-#[derive(Clone)]
-pub struct Clock;
+#[derive(Clone, Copy)]
+pub enum GeneratorMethod {
+    Next,
+    Send,
+    Done,
+}
+#[derive(Clone, Copy)]
+pub enum ChannelMethod {
+    Send,
+    Recv,
+}
+pub struct LoxEnum {
+    pub name: String,
+    pub variants: Vec<String>,
+}
+pub struct TailCall {
+    pub callee: CallableRet,
+    pub arguments: Vec<CallableRet>,
+    pub paren: Token,
+}
 #[derive(Clone)]
 pub struct FunctionInner {
-    pub declaration: FunctionStmtInner,
+    pub declaration: Rc<FunctionStmtInner>,
     pub closure: Rc<RefCell<Environment>>,
+    pub is_initializer: bool,
+    // Method decorators, applied to the method once it is bound.
+    pub decorators: Rc<Vec<CallableRet>>,
 }
 
 impl FunctionInner {
-    pub fn new(declaration: &FunctionStmtInner, closure: Rc<RefCell<Environment>>) -> Self {
+    pub fn new(
+        declaration: &FunctionStmtInner,
+        closure: Rc<RefCell<Environment>>,
+        is_initializer: bool,
+    ) -> Self {
         Self {
-            declaration: declaration.clone(),
+            declaration: Rc::new(declaration.clone()),
             closure,
+            is_initializer,
+            decorators: Rc::default(),
+        }
+    }
+
+    pub fn bind(&self, this: CallableRet) -> Self {
+        let mut env = Environment::new_with_enclosing(&self.closure);
+        env.define("this", Some(this));
+        Self {
+            declaration: Rc::clone(&self.declaration),
+            closure: Rc::new(RefCell::new(env)),
+            is_initializer: self.is_initializer,
+            decorators: Rc::default(),
         }
     }
 }
@@ -40,50 +103,131 @@ impl FunctionInner {
 impl Callable {
     pub fn arity(&self) -> usize {
         match self {
-            Callable::Native(_) => 0,
+            Callable::GeneratorMethod(_, GeneratorMethod::Next)
+            | Callable::GeneratorMethod(_, GeneratorMethod::Done)
+            | Callable::ChannelMethod(_, ChannelMethod::Recv)
+            | Callable::EnumValues(_) => 0,
+            Callable::GeneratorMethod(_, GeneratorMethod::Send)
+            | Callable::ChannelMethod(_, ChannelMethod::Send) => 1,
+            Callable::Native(native) => native.arity,
+            Callable::BoundNative(bound) => bound.method.arity(),
+            Callable::Class(class) => class.arity(),
             Callable::Function(func) => func.declaration.params.len(),
         }
     }
     pub fn call(
         &mut self,
         interpreter: &mut Interpreter,
-        arguments: &Vec<CallableRet>,
+        arguments: &[CallableRet],
     ) -> Result<CallableRet, RuntimeException> {
         match self {
-            Callable::Native(_) => {
-                let now = SystemTime::now();
-                let duration_since_epoch = now
-                    .duration_since(UNIX_EPOCH)
-                    .expect("system time earlier than unix epoch");
-                Ok(CallableRet::Value(LiteralValue::NumberLiteral(
-                    duration_since_epoch.as_secs_f64()
-                        + duration_since_epoch.subsec_nanos() as f64 * 1e-9,
-                )))
-            }
+            Callable::Native(native) => (native.function)(interpreter, arguments),
             Callable::Function(func) => {
-                let mut func_env = Environment::new_with_enclosing(&func.closure);
-                for i in 0..func.declaration.params.len() {
-                    func_env.define(
-                        &func.declaration.params[i].lexeme,
-                        Some(arguments[i].clone()),
-                    );
-                }
-                match func.declaration.body.as_ref() {
-                    Stmt::BlockStmt(func_block) => {
-                        match interpreter.execute_block(func_block, func_env) {
-                            Ok(_) => Ok(CallableRet::Value(LiteralValue::NilLiteral)),
-                            Err(e) => match e {
-                                RuntimeException::FunctionReturn(value) => match value {
-                                    Some(value) => Ok(value),
-                                    None => Ok(CallableRet::Value(LiteralValue::NilLiteral)),
+                let mut func = func.clone();
+                let mut arguments = arguments.to_vec();
+                loop {
+                    let declaration = Rc::clone(&func.declaration);
+                    let mut func_env = Environment::new_with_enclosing(&func.closure);
+                    for (param, arg) in declaration.params.iter().zip(&arguments) {
+                        func_env.define(&param.lexeme, Some(arg.clone()));
+                    }
+                    match declaration.body.as_ref() {
+                        Stmt::BlockStmt(func_block) if declaration.is_generator => {
+                            return Ok(CallableRet::Generator(Rc::new(RefCell::new(
+                                Generator::new(&declaration.name.lexeme, func_block, func_env),
+                            ))));
+                        }
+                        Stmt::BlockStmt(func_block) if func.is_initializer => {
+                            return match interpreter.execute_block(func_block, func_env) {
+                                Ok(_) | Err(RuntimeException::FunctionReturn(_)) => {
+                                    Ok(func.closure.borrow().map["this"].clone())
+                                }
+                                Err(e) => Err(e),
+                            };
+                        }
+                        Stmt::BlockStmt(func_block) => {
+                            match interpreter.execute_block(func_block, func_env) {
+                                Ok(_) => return Ok(CallableRet::Value(LiteralValue::NilLiteral)),
+                                Err(e) => match e {
+                                    RuntimeException::FunctionReturn(value) => {
+                                        return match value {
+                                            Some(value) => Ok(value),
+                                            None => {
+                                                Ok(CallableRet::Value(LiteralValue::NilLiteral))
+                                            }
+                                        }
+                                    }
+                                    // The body has already unwound, so a Lox callee
+                                    // runs in this loop instead of a nested call.
+                                    RuntimeException::TailCall(tail_call) => {
+                                        let TailCall {
+                                            callee,
+                                            arguments: tail_arguments,
+                                            paren,
+                                        } = *tail_call;
+                                        match callee {
+                                            CallableRet::Callable(Callable::Function(next))
+                                                if tail_arguments.len()
+                                                    == next.declaration.params.len() =>
+                                            {
+                                                func = next;
+                                                arguments = tail_arguments;
+                                            }
+                                            callee => {
+                                                return interpreter.call_value(
+                                                    callee,
+                                                    tail_arguments,
+                                                    &paren,
+                                                )
+                                            }
+                                        }
+                                    }
+                                    _ => return Err(e),
                                 },
-                                _ => Err(e),
-                            },
+                            }
                         }
+                        _ => unreachable!(),
                     }
-                    _ => unreachable!(),
                 }
             }
+            Callable::GeneratorMethod(generator, GeneratorMethod::Next) => {
+                match Generator::resume(generator, interpreter)? {
+                    Some(value) => Ok(value),
+                    None => Ok(CallableRet::Value(LiteralValue::NilLiteral)),
+                }
+            }
+            Callable::GeneratorMethod(generator, GeneratorMethod::Send) => {
+                match Generator::send(generator, interpreter, arguments[0].clone())? {
+                    Some(value) => Ok(value),
+                    None => Ok(CallableRet::Value(LiteralValue::NilLiteral)),
+                }
+            }
+            Callable::GeneratorMethod(generator, GeneratorMethod::Done) => Ok(CallableRet::Value(
+                LiteralValue::BoolLiteral(generator.borrow().is_done()),
+            )),
+            Callable::ChannelMethod(channel, ChannelMethod::Send) => {
+                channel
+                    .borrow_mut()
+                    .send(&mut interpreter.scheduler, arguments[0].clone());
+                Ok(CallableRet::Value(LiteralValue::NilLiteral))
+            }
+            // Like `sleep`, this has to be yielded by a task to take effect.
+            Callable::ChannelMethod(channel, ChannelMethod::Recv) => {
+                if !interpreter.scheduler.in_task() {
+                    return Err(RuntimeException::InvalidArgument(String::from(
+                        "recv() can only be awaited with yield inside a spawned task.",
+                    )));
+                }
+                Ok(CallableRet::Await(Await::Recv(channel.clone())))
+            }
+            Callable::BoundNative(bound) => bound.call(interpreter, arguments),
+            Callable::Class(class) => LoxClass::instantiate(class, interpreter, arguments),
+            Callable::EnumValues(lox_enum) => {
+                let variants = (0..lox_enum.variants.len())
+                    .map(|i| CallableRet::EnumVariant(lox_enum.clone(), i))
+                    .collect();
+                Ok(CallableRet::List(Rc::new(RefCell::new(variants))))
+            }
         }
     }
 }
@@ -93,15 +237,103 @@ impl Display for Callable {
         match self {
             Callable::Native(_) => write!(f, "<native fn>"),
             Callable::Function(func) => write!(f, "<fn {}>", func.declaration.name.lexeme),
+            Callable::Class(class) => write!(f, "{}", class.name),
+            Callable::GeneratorMethod(_, _)
+            | Callable::ChannelMethod(_, _)
+            | Callable::EnumValues(_)
+            | Callable::BoundNative(_) => {
+                write!(f, "<native fn>")
+            }
         }
     }
 }
 
 impl Display for CallableRet {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write(f, &mut Vec::new())
+    }
+}
+
+impl CallableRet {
+    // `seen` holds the lists and maps being printed, so a container that
+    // contains itself prints as `[...]` or `{...}` instead of recursing.
+    fn write(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        seen: &mut Vec<*const ()>,
+    ) -> std::fmt::Result {
         match self {
             CallableRet::Value(val) => write!(f, "{}", val),
             CallableRet::Callable(func) => write!(f, "{}", func),
+            CallableRet::Generator(generator) => {
+                write!(f, "<generator {}>", generator.borrow().name)
+            }
+            CallableRet::List(list) => {
+                let ptr = Rc::as_ptr(list) as *const ();
+                if seen.contains(&ptr) {
+                    return write!(f, "[...]");
+                }
+                seen.push(ptr);
+                write!(f, "[")?;
+                for (i, element) in list.borrow().iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    element.write_repr(f, seen)?;
+                }
+                seen.pop();
+                write!(f, "]")
+            }
+            CallableRet::Map(map) => {
+                let ptr = Rc::as_ptr(map) as *const ();
+                if seen.contains(&ptr) {
+                    return write!(f, "{{...}}");
+                }
+                seen.push(ptr);
+                write!(f, "{{")?;
+                for (i, (key, value)) in map.borrow().iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{key:?}: ")?;
+                    value.write_repr(f, seen)?;
+                }
+                seen.pop();
+                write!(f, "}}")
+            }
+            CallableRet::Range(start, end) => write!(f, "<range {start}..{end}>"),
+            CallableRet::Channel(_) => write!(f, "<channel>"),
+            CallableRet::Regex(regex) => write!(f, "<regex {}>", regex.as_str()),
+            CallableRet::Time(time) => write!(f, "{time}"),
+            CallableRet::Await(Await::Sleep(ms)) => write!(f, "<sleep {ms}>"),
+            CallableRet::Await(Await::Recv(_)) => write!(f, "<recv>"),
+            CallableRet::Enum(lox_enum) => write!(f, "<enum {}>", lox_enum.name),
+            CallableRet::Instance(instance) => {
+                write!(f, "{} instance", instance.borrow().class.name)
+            }
+            CallableRet::Trait(lox_trait) => write!(f, "<trait {}>", lox_trait.name),
+            CallableRet::EnumVariant(lox_enum, i) => {
+                write!(f, "{}.{}", lox_enum.name, lox_enum.variants[*i])
+            }
+        }
+    }
+
+    fn write_repr(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        seen: &mut Vec<*const ()>,
+    ) -> std::fmt::Result {
+        match self {
+            CallableRet::Value(LiteralValue::StringLiteral(s)) => write!(f, "{s:?}"),
+            _ => self.write(f, seen),
+        }
+    }
+
+    // Like Display, but strings are quoted the way they are inside lists.
+    pub fn repr(&self) -> String {
+        match self {
+            CallableRet::Value(LiteralValue::StringLiteral(s)) => format!("{s:?}"),
+            _ => self.to_string(),
         }
     }
 }
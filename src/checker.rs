@@ -0,0 +1,707 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::expression::*;
+use crate::interpreter::*;
+use crate::statement::*;
+use crate::token::*;
+
+#[derive(Clone, PartialEq)]
+pub enum Type {
+    Any,
+    Nil,
+    Bool,
+    Number,
+    String,
+    List,
+    Map,
+    Range,
+    Generator,
+    Function(Option<Vec<Type>>, Box<Type>),
+}
+
+pub struct TypeError {
+    line: usize,
+    msg: String,
+}
+
+#[derive(Clone)]
+struct Binding {
+    ty: Type,
+    annotated: bool,
+    function_depth: usize,
+}
+
+// Unannotated code is only checked where its type is certain: values whose
+// type can't be pinned down are `Any`, and `Any` is accepted everywhere, so
+// a program that runs fine is never rejected.
+pub struct TypeChecker {
+    scopes: Vec<HashMap<String, Binding>>,
+    function_depth: usize,
+    return_type: Option<Type>,
+    return_types: Vec<Type>,
+    pub errors: Vec<TypeError>,
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Type::Any => "Any",
+            Type::Nil => "Nil",
+            Type::Bool => "Bool",
+            Type::Number => "Number",
+            Type::String => "String",
+            Type::List => "List",
+            Type::Map => "Map",
+            Type::Range => "Range",
+            Type::Generator => "Generator",
+            Type::Function(_, _) => "Function",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl fmt::Display for TypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\n[line {}]", self.msg, self.line)
+    }
+}
+
+impl Type {
+    fn accepts(&self, actual: &Type) -> bool {
+        match (self, actual) {
+            (Type::Any, _) | (_, Type::Any) => true,
+            (Type::Function(_, _), Type::Function(_, _)) => true,
+            (expected, actual) => expected == actual,
+        }
+    }
+
+    fn join(&self, other: &Type) -> Type {
+        if self == other {
+            self.clone()
+        } else {
+            Type::Any
+        }
+    }
+
+    fn is_callable(&self) -> bool {
+        matches!(self, Type::Any | Type::Function(_, _))
+    }
+}
+
+impl Default for TypeChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TypeChecker {
+    pub fn new() -> Self {
+        let mut globals = HashMap::new();
+        for (name, ty) in [
+            (
+                "clock",
+                Type::Function(Some(vec![]), Box::new(Type::Number)),
+            ),
+            (
+                "range",
+                Type::Function(
+                    Some(vec![Type::Number, Type::Number]),
+                    Box::new(Type::Range),
+                ),
+            ),
+            (
+                "spawn",
+                Type::Function(Some(vec![Type::Any]), Box::new(Type::Nil)),
+            ),
+            (
+                "sleep",
+                Type::Function(Some(vec![Type::Number]), Box::new(Type::Any)),
+            ),
+            ("channel", Type::Function(Some(vec![]), Box::new(Type::Any))),
+            (
+                "try",
+                Type::Function(Some(vec![Type::Any, Type::Any]), Box::new(Type::Any)),
+            ),
+        ] {
+            globals.insert(
+                String::from(name),
+                Binding {
+                    ty,
+                    annotated: false,
+                    function_depth: 0,
+                },
+            );
+        }
+        Self {
+            scopes: vec![globals],
+            function_depth: 0,
+            return_type: None,
+            return_types: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn check(&mut self, stmts: &[Stmt]) {
+        for stmt in stmts {
+            self.check_stmt(stmt);
+        }
+    }
+
+    fn check_stmt(&mut self, stmt: &Stmt) {
+        stmt.accept(self)
+    }
+
+    fn check_expr(&mut self, expr: &Expr) -> Type {
+        expr.accept(self)
+    }
+
+    fn error(&mut self, line: usize, msg: String) {
+        self.errors.push(TypeError { line, msg });
+    }
+
+    fn resolve_annotation(&mut self, annotation: &Option<Token>) -> Option<Type> {
+        let name = annotation.as_ref()?;
+        let ty = match name.lexeme.as_str() {
+            "Any" => Type::Any,
+            "Nil" => Type::Nil,
+            "Bool" => Type::Bool,
+            "Number" => Type::Number,
+            "String" => Type::String,
+            "List" => Type::List,
+            "Map" => Type::Map,
+            "Range" => Type::Range,
+            "Generator" => Type::Generator,
+            "Function" => Type::Function(None, Box::new(Type::Any)),
+            _ => {
+                self.error(name.line, format!("Unknown type '{}'.", name.lexeme));
+                Type::Any
+            }
+        };
+        Some(ty)
+    }
+
+    fn declare(&mut self, name: &Token, ty: Type, annotated: bool) {
+        let binding = Binding {
+            ty,
+            annotated,
+            function_depth: self.function_depth,
+        };
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .insert(name.lexeme.clone(), binding);
+    }
+
+    fn lookup(&mut self, name: &Token) -> Option<&mut Binding> {
+        self.scopes
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.get_mut(&name.lexeme))
+    }
+
+    fn declare_pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Name(name) => self.declare(name, Type::Any, false),
+            Pattern::List(_, patterns) => {
+                for pattern in patterns {
+                    self.declare_pattern(pattern);
+                }
+            }
+            Pattern::Map(_, names) => {
+                for name in names {
+                    self.declare(name, Type::Any, false);
+                }
+            }
+        }
+    }
+
+    fn assign_pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Name(name) => {
+                if let Some(binding) = self.lookup(name) {
+                    if !binding.annotated {
+                        binding.ty = Type::Any;
+                    }
+                }
+            }
+            Pattern::List(_, patterns) => {
+                for pattern in patterns {
+                    self.assign_pattern(pattern);
+                }
+            }
+            Pattern::Map(_, names) => {
+                for name in names {
+                    self.assign_pattern(&Pattern::Name(name.clone()));
+                }
+            }
+        }
+    }
+
+    // A loop body runs again after its own later statements, so every
+    // binding from outside the loop starts the body with the join of the
+    // types it has on each pass. Passes before the last only widen those
+    // types; their errors are dropped.
+    fn check_loop(&mut self, condition: Option<&Expr>, body: &Stmt) {
+        loop {
+            let before = self.scopes.clone();
+            let errors = self.errors.len();
+            let returns = self.return_types.len();
+            if let Some(condition) = condition {
+                self.check_expr(condition);
+            }
+            self.check_stmt(body);
+            self.errors.truncate(errors);
+            self.return_types.truncate(returns);
+            let after = std::mem::replace(&mut self.scopes, before);
+            let mut changed = false;
+            for (scope, after) in self.scopes.iter_mut().zip(after) {
+                for (name, binding) in scope.iter_mut() {
+                    let Some(after) = after.get(name) else {
+                        continue;
+                    };
+                    let joined = binding.ty.join(&after.ty);
+                    if joined != binding.ty {
+                        binding.ty = joined;
+                        changed = true;
+                    }
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        if let Some(condition) = condition {
+            self.check_expr(condition);
+        }
+        self.check_stmt(body);
+    }
+
+    fn always_returns(stmt: &Stmt) -> bool {
+        match stmt {
+            Stmt::ReturnStmt(_) => true,
+            Stmt::BlockStmt(block) => block.0.iter().any(|stmt| Self::always_returns(stmt)),
+            Stmt::IfStmt(branch) => match &branch.else_branch {
+                Some(else_branch) => {
+                    Self::always_returns(&branch.then_branch) && Self::always_returns(else_branch)
+                }
+                None => false,
+            },
+            _ => false,
+        }
+    }
+
+    fn check_numbers(&mut self, operator: &Token, left: &Type, right: &Type) {
+        if !Type::Number.accepts(left) || !Type::Number.accepts(right) {
+            self.error(operator.line, String::from(OPERANDS_MUST_BE_NUMBERS));
+        }
+    }
+}
+
+impl ExprVisitor<Type> for TypeChecker {
+    fn visit_binary(&mut self, binary: &Binary) -> Type {
+        let left = self.check_expr(&binary.left);
+        let right = self.check_expr(&binary.right);
+        match binary.operator.ttype {
+            TokenType::MINUS | TokenType::STAR | TokenType::SLASH => {
+                self.check_numbers(&binary.operator, &left, &right);
+                Type::Number
+            }
+            TokenType::GREATER
+            | TokenType::GREATER_EQUAL
+            | TokenType::LESS
+            | TokenType::LESS_EQUAL => {
+                self.check_numbers(&binary.operator, &left, &right);
+                Type::Bool
+            }
+            TokenType::PLUS => match (&left, &right) {
+                (Type::Number | Type::Any, Type::Number) | (Type::Number, Type::Any) => {
+                    Type::Number
+                }
+                (Type::String | Type::Any, Type::String) | (Type::String, Type::Any) => {
+                    Type::String
+                }
+                (Type::Any, Type::Any) => Type::Any,
+                _ => {
+                    self.error(
+                        binary.operator.line,
+                        String::from(OPERANDS_MUST_BE_NUMBERS_OR_STRINGS),
+                    );
+                    Type::Any
+                }
+            },
+            _ => Type::Bool,
+        }
+    }
+
+    fn visit_unary(&mut self, unary: &Unary) -> Type {
+        let right = self.check_expr(&unary.right);
+        match unary.operator.ttype {
+            TokenType::MINUS => {
+                if !Type::Number.accepts(&right) {
+                    self.error(unary.operator.line, String::from(OPERAND_MUST_BE_NUMBER));
+                }
+                Type::Number
+            }
+            _ => Type::Bool,
+        }
+    }
+
+    fn visit_literal(&mut self, literal: &Literal) -> Type {
+        match literal.value {
+            LiteralValue::NumberLiteral(_) => Type::Number,
+            LiteralValue::StringLiteral(_) => Type::String,
+            LiteralValue::BoolLiteral(_) => Type::Bool,
+            LiteralValue::NilLiteral => Type::Nil,
+        }
+    }
+
+    fn visit_grouping(&mut self, grouping: &Grouping) -> Type {
+        self.check_expr(&grouping.expression)
+    }
+
+    fn visit_var(&mut self, var: &Var) -> Type {
+        let function_depth = self.function_depth;
+        match self.lookup(&var.name) {
+            // A function body may run after a captured variable was reassigned,
+            // so only trust its type if it can't have changed.
+            Some(binding)
+                if binding.annotated
+                    || binding.function_depth == function_depth
+                    || matches!(binding.ty, Type::Function(_, _)) =>
+            {
+                binding.ty.clone()
+            }
+            _ => Type::Any,
+        }
+    }
+
+    fn visit_assignment(&mut self, assignment: &Assignment) -> Type {
+        let value = self.check_expr(&assignment.value);
+        let mismatch = match self.lookup(&assignment.name) {
+            Some(binding) if binding.annotated => {
+                (!binding.ty.accepts(&value)).then(|| binding.ty.clone())
+            }
+            Some(binding) => {
+                binding.ty = binding.ty.join(&value);
+                None
+            }
+            None => None,
+        };
+        if let Some(expected) = mismatch {
+            self.error(
+                assignment.name.line,
+                format!(
+                    "Can't assign {} to '{}' of type {}.",
+                    value, assignment.name.lexeme, expected
+                ),
+            );
+        }
+        value
+    }
+
+    fn visit_logical(&mut self, logical: &Logical) -> Type {
+        let left = self.check_expr(&logical.left);
+        let right = self.check_expr(&logical.right);
+        left.join(&right)
+    }
+
+    fn visit_call(&mut self, call: &Call) -> Type {
+        let callee = self.check_expr(&call.callee);
+        let arguments: Vec<Type> = call
+            .arguments
+            .iter()
+            .map(|arg| self.check_expr(arg))
+            .collect();
+        if !callee.is_callable() {
+            let e = RuntimeException::InvalidCallable(call.paren.clone(), String::new());
+            self.error(call.paren.line, e.to_string());
+            return Type::Any;
+        }
+        match callee {
+            Type::Function(Some(params), ret) => {
+                if params.len() != arguments.len() {
+                    let e = RuntimeException::UnmatchedArity(params.len(), arguments.len());
+                    self.error(call.paren.line, e.to_string());
+                } else {
+                    for (i, (param, arg)) in params.iter().zip(&arguments).enumerate() {
+                        if !param.accepts(arg) {
+                            self.error(
+                                call.paren.line,
+                                format!("Argument {} must be {} but got {}.", i + 1, param, arg),
+                            );
+                        }
+                    }
+                }
+                *ret
+            }
+            Type::Function(None, ret) => *ret,
+            _ => Type::Any,
+        }
+    }
+
+    fn visit_get(&mut self, get: &Get) -> Type {
+        self.check_expr(&get.object);
+        Type::Any
+    }
+
+    fn visit_list(&mut self, list: &List) -> Type {
+        for element in &list.elements {
+            self.check_expr(element);
+        }
+        Type::List
+    }
+
+    fn visit_map(&mut self, map: &Map) -> Type {
+        for (key, value) in &map.entries {
+            let key = self.check_expr(key);
+            if !Type::String.accepts(&key) {
+                self.error(map.brace.line, String::from(MAP_KEYS_MUST_BE_STRINGS));
+            }
+            self.check_expr(value);
+        }
+        Type::Map
+    }
+
+    fn visit_index(&mut self, index: &Index) -> Type {
+        let object = self.check_expr(&index.object);
+        self.check_expr(&index.index);
+        if !matches!(object, Type::Any | Type::List | Type::Map) {
+            self.error(index.bracket.line, String::from(NOT_INDEXABLE));
+        }
+        Type::Any
+    }
+
+    fn visit_index_set(&mut self, index_set: &IndexSet) -> Type {
+        let object = self.check_expr(&index_set.object);
+        self.check_expr(&index_set.index);
+        if !matches!(object, Type::Any | Type::List | Type::Map) {
+            self.error(index_set.bracket.line, String::from(NOT_INDEXABLE));
+        }
+        self.check_expr(&index_set.value)
+    }
+
+    fn visit_destructure(&mut self, destructure: &Destructure) -> Type {
+        let value = self.check_expr(&destructure.value);
+        self.assign_pattern(&destructure.pattern);
+        value
+    }
+
+    fn visit_set(&mut self, set: &Set) -> Type {
+        self.check_expr(&set.object);
+        self.check_expr(&set.value)
+    }
+
+    fn visit_this(&mut self, _this: &This) -> Type {
+        Type::Any
+    }
+
+    fn visit_super(&mut self, _super_expr: &Super) -> Type {
+        Type::Any
+    }
+}
+
+impl StmtVisitor<()> for TypeChecker {
+    fn visit_expr(&mut self, expr: &ExprStmtInner) {
+        self.check_expr(&expr.0);
+    }
+
+    fn visit_print(&mut self, print: &PrintStmtInner) {
+        self.check_expr(&print.0);
+    }
+
+    fn visit_var(&mut self, var: &VarStmtInner) {
+        let value = var.1.as_ref().map(|init| self.check_expr(init));
+        match &var.0 {
+            Pattern::Name(name) => match self.resolve_annotation(&var.2) {
+                Some(expected) => {
+                    if let Some(value) = value.filter(|value| !expected.accepts(value)) {
+                        self.error(
+                            name.line,
+                            format!(
+                                "Can't assign {} to '{}' of type {}.",
+                                value, name.lexeme, expected
+                            ),
+                        );
+                    }
+                    self.declare(name, expected, true);
+                }
+                None => self.declare(name, value.unwrap_or(Type::Nil), false),
+            },
+            pattern => self.declare_pattern(pattern),
+        }
+    }
+
+    fn visit_block(&mut self, stmts: &BlockStmtInner) {
+        self.scopes.push(HashMap::new());
+        for stmt in &stmts.0 {
+            self.check_stmt(stmt);
+        }
+        self.scopes.pop();
+    }
+
+    fn visit_if(&mut self, branch: &IfStmtInner) {
+        self.check_expr(&branch.condition);
+        self.check_stmt(&branch.then_branch);
+        if let Some(else_branch) = &branch.else_branch {
+            self.check_stmt(else_branch);
+        }
+    }
+
+    fn visit_while(&mut self, while_stmt: &WhileStmtInner) {
+        self.check_loop(Some(&while_stmt.condition), &while_stmt.body);
+    }
+
+    fn visit_function(&mut self, func_stmt: &FunctionStmtInner) {
+        let params: Vec<Type> = func_stmt
+            .param_types
+            .iter()
+            .map(|annotation| self.resolve_annotation(annotation).unwrap_or(Type::Any))
+            .collect();
+        let declared_return = self.resolve_annotation(&func_stmt.return_type);
+        let signature_return = if func_stmt.is_generator {
+            Type::Generator
+        } else {
+            declared_return.clone().unwrap_or(Type::Any)
+        };
+        for decorator in &func_stmt.decorators {
+            self.check_expr(decorator);
+        }
+        let signature = if func_stmt.decorators.is_empty() {
+            Type::Function(Some(params.clone()), Box::new(signature_return))
+        } else {
+            Type::Any
+        };
+        self.declare(&func_stmt.name, signature, false);
+
+        let enclosing_return = std::mem::replace(&mut self.return_type, declared_return);
+        let enclosing_returns = std::mem::take(&mut self.return_types);
+        self.function_depth += 1;
+        self.scopes.push(HashMap::new());
+        for ((param, ty), annotation) in func_stmt
+            .params
+            .iter()
+            .zip(params.iter())
+            .zip(&func_stmt.param_types)
+        {
+            self.declare(param, ty.clone(), annotation.is_some());
+        }
+        if let Stmt::BlockStmt(body) = func_stmt.body.as_ref() {
+            for stmt in &body.0 {
+                self.check_stmt(stmt);
+            }
+        }
+        self.scopes.pop();
+        self.function_depth -= 1;
+        let returns = std::mem::replace(&mut self.return_types, enclosing_returns);
+        self.return_type = enclosing_return;
+
+        if func_stmt.is_generator || func_stmt.return_type.is_some() {
+            return;
+        }
+        let mut inferred = returns.first().cloned().unwrap_or(Type::Nil);
+        for ty in &returns {
+            inferred = inferred.join(ty);
+        }
+        if !Self::always_returns(&func_stmt.body) {
+            inferred = inferred.join(&Type::Nil);
+        }
+        self.declare(
+            &func_stmt.name,
+            Type::Function(Some(params), Box::new(inferred)),
+            false,
+        );
+    }
+
+    fn visit_return(&mut self, return_stmt: &ReturnStmtInner) {
+        let value = match &return_stmt.value {
+            Some(value) => self.check_expr(value),
+            None => Type::Nil,
+        };
+        if self.function_depth == 0 {
+            self.error(
+                return_stmt.keyword.line,
+                String::from("Can't return from top-level code."),
+            );
+        }
+        if let Some(expected) = self.return_type.clone() {
+            if !expected.accepts(&value) {
+                self.error(
+                    return_stmt.keyword.line,
+                    format!("Expected a return value of type {expected} but got {value}."),
+                );
+            }
+        }
+        self.return_types.push(value);
+    }
+
+    fn visit_yield(&mut self, yield_stmt: &YieldStmtInner) {
+        if let Some(value) = &yield_stmt.value {
+            self.check_expr(value);
+        }
+        match &yield_stmt.target {
+            Some(YieldTarget::Declare(name)) => self.declare(name, Type::Any, false),
+            Some(YieldTarget::Assign(name)) => self.assign_pattern(&Pattern::Name(name.clone())),
+            None => {}
+        }
+    }
+
+    fn visit_for_in(&mut self, for_in: &ForInStmtInner) {
+        let iterable = self.check_expr(&for_in.iterable);
+        let element = match iterable {
+            Type::Map | Type::String => Type::String,
+            Type::Range => Type::Number,
+            Type::Number | Type::Bool | Type::Nil | Type::Function(_, _) => {
+                self.error(for_in.keyword.line, String::from(NOT_ITERABLE));
+                Type::Any
+            }
+            _ => Type::Any,
+        };
+        self.scopes.push(HashMap::new());
+        self.declare(&for_in.name, element, false);
+        self.check_loop(None, &for_in.body);
+        self.scopes.pop();
+    }
+
+    fn visit_class(&mut self, class: &ClassStmtInner) {
+        self.declare(&class.name, Type::Any, false);
+        for parent in class.superclass.iter().chain(&class.traits) {
+            ExprVisitor::visit_var(self, parent);
+        }
+        self.scopes.push(HashMap::new());
+        for method in class
+            .methods
+            .iter()
+            .chain(&class.getters)
+            .chain(&class.setters)
+            .chain(&class.class_methods)
+        {
+            self.visit_function(method);
+        }
+        self.scopes.pop();
+    }
+
+    fn visit_trait(&mut self, trait_stmt: &TraitStmtInner) {
+        self.declare(&trait_stmt.name, Type::Any, false);
+        self.scopes.push(HashMap::new());
+        for method in &trait_stmt.methods {
+            self.visit_function(method);
+        }
+        self.scopes.pop();
+    }
+
+    fn visit_enum(&mut self, enum_stmt: &EnumStmtInner) {
+        self.declare(&enum_stmt.name, Type::Any, false);
+    }
+
+    fn visit_defer(&mut self, defer: &DeferStmtInner) {
+        self.check_expr(&defer.expr);
+    }
+
+    fn visit_assert(&mut self, assert: &AssertStmtInner) {
+        self.check_expr(&assert.condition);
+        if let Some(message) = &assert.message {
+            self.check_expr(message);
+        }
+    }
+}
@@ -0,0 +1,333 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use crate::callable::*;
+use crate::interpreter::*;
+use crate::token::Token;
+
+pub struct LoxClass {
+    pub name: String,
+    pub superclass: Option<Rc<LoxClass>>,
+    pub methods: HashMap<String, FunctionInner>,
+    pub getters: HashMap<String, FunctionInner>,
+    pub setters: HashMap<String, FunctionInner>,
+    pub class_methods: HashMap<String, FunctionInner>,
+    pub statics: RefCell<HashMap<String, CallableRet>>,
+    // Decorated static methods, bound and decorated on first access.
+    pub bound: RefCell<HashMap<String, CallableRet>>,
+    // Members marked `private` or named `#name`.
+    pub private: HashSet<String>,
+}
+
+pub struct LoxTrait {
+    pub name: String,
+    pub methods: HashMap<String, FunctionInner>,
+    pub required: Vec<String>,
+}
+
+pub struct Instance {
+    pub class: Rc<LoxClass>,
+    pub fields: HashMap<String, CallableRet>,
+    // Decorated methods are bound and decorated once per instance, so state a
+    // decorator keeps (a memo table, a call count) lives as long as the object.
+    pub bound: HashMap<String, CallableRet>,
+    // `#name` fields, kept apart for each class that writes them so a subclass
+    // can reuse a name without clashing with its superclass.
+    pub private_fields: HashMap<(*const LoxClass, String), CallableRet>,
+}
+
+impl LoxClass {
+    // Returns the member together with the class that declares it.
+    pub fn find(
+        &self,
+        name: &str,
+        members: fn(&LoxClass) -> &HashMap<String, FunctionInner>,
+    ) -> Option<(&LoxClass, &FunctionInner)> {
+        match members(self).get(name) {
+            Some(member) => Some((self, member)),
+            None => self.superclass.as_ref()?.find(name, members),
+        }
+    }
+
+    fn is_subclass_of(&self, other: &LoxClass) -> bool {
+        std::ptr::eq(self, other)
+            || self
+                .superclass
+                .as_ref()
+                .is_some_and(|superclass| superclass.is_subclass_of(other))
+    }
+
+    // Private members can only be reached from code written inside the body
+    // of the class that declares them; subclasses don't see them either.
+    pub fn check_access(
+        &self,
+        name: &Token,
+        interpreter: &Interpreter,
+    ) -> Result<(), RuntimeException> {
+        if !self.private.contains(&name.lexeme) {
+            return Ok(());
+        }
+        match interpreter.enclosing_class() {
+            Some(class) if std::ptr::eq(class.as_ref(), self) => Ok(()),
+            _ => Err(RuntimeException::PrivateAccess(
+                name.clone(),
+                self.name.clone(),
+            )),
+        }
+    }
+
+    // A `#name` on an object resolves against the class whose body the access
+    // is written in, which the object has to be an instance of.
+    fn private_scope(
+        &self,
+        name: &Token,
+        interpreter: &Interpreter,
+    ) -> Result<Rc<LoxClass>, RuntimeException> {
+        match interpreter.enclosing_class() {
+            Some(scope) if self.is_subclass_of(&scope) => Ok(scope),
+            _ => Err(RuntimeException::PrivateAccess(
+                name.clone(),
+                self.name.clone(),
+            )),
+        }
+    }
+
+    pub fn find_method(&self, name: &str) -> Option<FunctionInner> {
+        self.find(name, |class| &class.methods)
+            .map(|(_, method)| method.clone())
+    }
+
+    pub fn arity(&self) -> usize {
+        self.find_method("init")
+            .map_or(0, |init| init.declaration.params.len())
+    }
+
+    pub fn instantiate(
+        class: &Rc<LoxClass>,
+        interpreter: &mut Interpreter,
+        arguments: &[CallableRet],
+    ) -> Result<CallableRet, RuntimeException> {
+        let instance = CallableRet::Instance(Rc::new(RefCell::new(Instance {
+            class: class.clone(),
+            fields: HashMap::new(),
+            bound: HashMap::new(),
+            private_fields: HashMap::new(),
+        })));
+        if let Some(init) = class.find_method("init") {
+            Callable::Function(init.bind(instance.clone())).call(interpreter, arguments)?;
+        }
+        Ok(instance)
+    }
+
+    // Static methods are bound with `this` as the class itself, so they can
+    // reach static fields and other static methods. Static fields are read
+    // through the superclass chain but always written on the class itself.
+    pub fn get(
+        class: &Rc<LoxClass>,
+        name: &Token,
+        interpreter: &mut Interpreter,
+    ) -> Result<CallableRet, RuntimeException> {
+        if name.lexeme.starts_with('#') {
+            class.private_scope(name, interpreter)?;
+        }
+        if let Some(value) = class.find_static(&name.lexeme) {
+            return Ok(value);
+        }
+        let Some((owner, method)) = class.find(&name.lexeme, |class| &class.class_methods) else {
+            return Err(RuntimeException::UndefinedProperty(name.clone()));
+        };
+        owner.check_access(name, interpreter)?;
+        if let Some(value) = class.bound.borrow().get(&name.lexeme) {
+            return Ok(value.clone());
+        }
+        let method = method.clone();
+        let this = CallableRet::Callable(Callable::Class(class.clone()));
+        let bound = interpreter.bind_method(&method, this)?;
+        if !method.decorators.is_empty() {
+            class
+                .bound
+                .borrow_mut()
+                .insert(name.lexeme.clone(), bound.clone());
+        }
+        Ok(bound)
+    }
+
+    fn find_static(&self, name: &str) -> Option<CallableRet> {
+        match self.statics.borrow().get(name) {
+            Some(value) => Some(value.clone()),
+            None => self
+                .superclass
+                .as_ref()
+                .and_then(|superclass| superclass.find_static(name)),
+        }
+    }
+
+    pub fn set(
+        class: &Rc<LoxClass>,
+        name: &Token,
+        value: CallableRet,
+        interpreter: &Interpreter,
+    ) -> Result<(), RuntimeException> {
+        if name.lexeme.starts_with('#') {
+            class.private_scope(name, interpreter)?;
+        }
+        class
+            .statics
+            .borrow_mut()
+            .insert(name.lexeme.clone(), value);
+        Ok(())
+    }
+}
+
+impl LoxTrait {
+    // Trait methods are copied into the class, but never over the class's own
+    // methods. Two traits providing the same method is a conflict unless the
+    // class settles it by defining that method itself.
+    pub fn include(
+        traits: &[Rc<LoxTrait>],
+        class: &mut LoxClass,
+        name: &Token,
+    ) -> Result<(), RuntimeException> {
+        let mut provided: HashMap<String, &str> = HashMap::new();
+        for lox_trait in traits {
+            let mut methods: Vec<_> = lox_trait.methods.iter().collect();
+            methods.sort_by_key(|(method_name, _)| *method_name);
+            for (method_name, method) in methods {
+                if class.methods.contains_key(method_name) && !provided.contains_key(method_name) {
+                    continue;
+                }
+                if let Some(other) = provided.insert(method_name.clone(), &lox_trait.name) {
+                    return Err(RuntimeException::TraitError(
+                        name.clone(),
+                        format!(
+                            "Method '{}' is provided by both '{}' and '{}'.",
+                            method_name, other, lox_trait.name
+                        ),
+                    ));
+                }
+                class.methods.insert(method_name.clone(), method.clone());
+            }
+        }
+        for lox_trait in traits {
+            for required in &lox_trait.required {
+                if class.find_method(required).is_none() {
+                    return Err(RuntimeException::TraitError(
+                        name.clone(),
+                        format!(
+                            "Class '{}' must implement '{}' required by trait '{}'.",
+                            class.name, required, lox_trait.name
+                        ),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Instance {
+    pub fn get(
+        instance: &Rc<RefCell<Instance>>,
+        name: &Token,
+        interpreter: &mut Interpreter,
+    ) -> Result<CallableRet, RuntimeException> {
+        if name.lexeme.starts_with('#') {
+            return Self::get_private(instance, name, interpreter);
+        }
+        if let Some(value) = instance.borrow().fields.get(&name.lexeme) {
+            return Ok(value.clone());
+        }
+        let class = instance.borrow().class.clone();
+        if let Some((owner, getter)) = class.find(&name.lexeme, |class| &class.getters) {
+            owner.check_access(name, interpreter)?;
+            return Callable::Function(getter.bind(CallableRet::Instance(instance.clone())))
+                .call(interpreter, &[]);
+        }
+        if let Some((owner, _)) = class.find(&name.lexeme, |class| &class.methods) {
+            owner.check_access(name, interpreter)?;
+        }
+        Self::method(instance, &name.lexeme, interpreter)?
+            .ok_or_else(|| RuntimeException::UndefinedProperty(name.clone()))
+    }
+
+    fn get_private(
+        instance: &Rc<RefCell<Instance>>,
+        name: &Token,
+        interpreter: &mut Interpreter,
+    ) -> Result<CallableRet, RuntimeException> {
+        let scope = instance.borrow().class.private_scope(name, interpreter)?;
+        let key = (Rc::as_ptr(&scope), name.lexeme.clone());
+        if let Some(value) = instance.borrow().private_fields.get(&key) {
+            return Ok(value.clone());
+        }
+        let this = CallableRet::Instance(instance.clone());
+        if let Some(getter) = scope.getters.get(&name.lexeme) {
+            return Callable::Function(getter.bind(this)).call(interpreter, &[]);
+        }
+        match scope.methods.get(&name.lexeme) {
+            Some(method) => interpreter.bind_method(method, this),
+            None => Err(RuntimeException::UndefinedProperty(name.clone())),
+        }
+    }
+
+    pub fn method(
+        instance: &Rc<RefCell<Instance>>,
+        name: &str,
+        interpreter: &mut Interpreter,
+    ) -> Result<Option<CallableRet>, RuntimeException> {
+        if let Some(bound) = instance.borrow().bound.get(name) {
+            return Ok(Some(bound.clone()));
+        }
+        let Some(method) = instance.borrow().class.find_method(name) else {
+            return Ok(None);
+        };
+        let bound = interpreter.bind_method(&method, CallableRet::Instance(instance.clone()))?;
+        if !method.decorators.is_empty() {
+            instance
+                .borrow_mut()
+                .bound
+                .insert(String::from(name), bound.clone());
+        }
+        Ok(Some(bound))
+    }
+
+    pub fn set(
+        instance: &Rc<RefCell<Instance>>,
+        name: &Token,
+        value: CallableRet,
+        interpreter: &mut Interpreter,
+    ) -> Result<(), RuntimeException> {
+        let class = instance.borrow().class.clone();
+        if name.lexeme.starts_with('#') {
+            let scope = class.private_scope(name, interpreter)?;
+            match scope.setters.get(&name.lexeme) {
+                Some(setter) => {
+                    Callable::Function(setter.bind(CallableRet::Instance(instance.clone())))
+                        .call(interpreter, &[value])?;
+                }
+                None => {
+                    instance
+                        .borrow_mut()
+                        .private_fields
+                        .insert((Rc::as_ptr(&scope), name.lexeme.clone()), value);
+                }
+            }
+            return Ok(());
+        }
+        match class.find(&name.lexeme, |class| &class.setters) {
+            Some((owner, setter)) => {
+                owner.check_access(name, interpreter)?;
+                Callable::Function(setter.bind(CallableRet::Instance(instance.clone())))
+                    .call(interpreter, &[value])?;
+            }
+            None => {
+                instance
+                    .borrow_mut()
+                    .fields
+                    .insert(name.lexeme.clone(), value);
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,13 +1,21 @@
 use crate::callable::CallableRet;
+use crate::class::LoxClass;
+use crate::expression::Expr;
 use crate::{interpreter::RuntimeException, token::*};
 use std::cell::RefCell;
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
 
 pub struct Environment {
     pub enclosing: Option<Rc<RefCell<Environment>>>,
     pub map: HashMap<String, CallableRet>,
+    // `defer`red expressions, run last-in first-out when the block that owns
+    // this environment exits.
+    pub deferred: Vec<Expr>,
+    // Set on the environment a class's members close over, so code can tell
+    // which class body it was written in.
+    pub class: Option<Weak<LoxClass>>,
 }
 
 impl Environment {
@@ -15,12 +23,16 @@ impl Environment {
         Rc::new(RefCell::new(Self {
             enclosing: None,
             map: HashMap::new(),
+            deferred: Vec::new(),
+            class: None,
         }))
     }
     pub fn new_with_enclosing(enclosing: &Rc<RefCell<Self>>) -> Environment {
         Self {
             enclosing: Some(Rc::clone(enclosing)),
             map: HashMap::new(),
+            deferred: Vec::new(),
+            class: None,
         }
     }
     pub fn define(&mut self, name: &str, value: Option<CallableRet>) {
@@ -42,6 +54,12 @@ impl Environment {
             },
         }
     }
+    pub fn class(&self) -> Option<Rc<LoxClass>> {
+        match &self.class {
+            Some(class) => class.upgrade(),
+            None => self.enclosing.as_ref()?.borrow().class(),
+        }
+    }
     pub fn get(&self, name: &Token) -> Result<CallableRet, RuntimeException> {
         match self.map.get(&name.lexeme) {
             Some(val) => Ok(val.clone()),
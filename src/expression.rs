@@ -10,6 +10,15 @@ pub trait ExprVisitor<R> {
     fn visit_assignment(&mut self, assignment: &Assignment) -> R;
     fn visit_logical(&mut self, logical: &Logical) -> R;
     fn visit_call(&mut self, call: &Call) -> R;
+    fn visit_get(&mut self, get: &Get) -> R;
+    fn visit_list(&mut self, list: &List) -> R;
+    fn visit_map(&mut self, map: &Map) -> R;
+    fn visit_index(&mut self, index: &Index) -> R;
+    fn visit_index_set(&mut self, index_set: &IndexSet) -> R;
+    fn visit_destructure(&mut self, destructure: &Destructure) -> R;
+    fn visit_set(&mut self, set: &Set) -> R;
+    fn visit_this(&mut self, this: &This) -> R;
+    fn visit_super(&mut self, super_expr: &Super) -> R;
 }
 
 pub trait ExprAccept<R> {
@@ -26,6 +35,22 @@ pub enum Expr {
     AssignmentExpr(Assignment),
     LogicalExpr(Logical),
     CallExpr(Call),
+    GetExpr(Get),
+    ListExpr(List),
+    MapExpr(Map),
+    IndexExpr(Index),
+    IndexSetExpr(IndexSet),
+    DestructureExpr(Destructure),
+    SetExpr(Set),
+    ThisExpr(This),
+    SuperExpr(Super),
+}
+
+#[derive(Clone)]
+pub enum Pattern {
+    Name(Token),
+    List(Token, Vec<Pattern>),
+    Map(Token, Vec<Token>),
 }
 
 #[derive(Clone)]
@@ -76,6 +101,63 @@ pub struct Call {
     pub arguments: Vec<Box<Expr>>,
 }
 
+#[derive(Clone)]
+pub struct Get {
+    pub object: Box<Expr>,
+    pub name: Token,
+}
+
+#[derive(Clone)]
+pub struct List {
+    pub bracket: Token,
+    pub elements: Vec<Box<Expr>>,
+}
+
+#[derive(Clone)]
+pub struct Map {
+    pub brace: Token,
+    pub entries: Vec<(Box<Expr>, Box<Expr>)>,
+}
+
+#[derive(Clone)]
+pub struct Index {
+    pub object: Box<Expr>,
+    pub bracket: Token,
+    pub index: Box<Expr>,
+}
+
+#[derive(Clone)]
+pub struct IndexSet {
+    pub object: Box<Expr>,
+    pub bracket: Token,
+    pub index: Box<Expr>,
+    pub value: Box<Expr>,
+}
+
+#[derive(Clone)]
+pub struct Destructure {
+    pub pattern: Pattern,
+    pub value: Box<Expr>,
+}
+
+#[derive(Clone)]
+pub struct Set {
+    pub object: Box<Expr>,
+    pub name: Token,
+    pub value: Box<Expr>,
+}
+
+#[derive(Clone)]
+pub struct This {
+    pub keyword: Token,
+}
+
+#[derive(Clone)]
+pub struct Super {
+    pub keyword: Token,
+    pub method: Token,
+}
+
 impl Binary {
     pub fn new(left: Box<Expr>, operator: Token, right: Box<Expr>) -> Self {
         Self {
@@ -136,6 +218,73 @@ impl Call {
     }
 }
 
+impl Get {
+    pub fn new(object: Box<Expr>, name: Token) -> Self {
+        Self { object, name }
+    }
+}
+
+impl List {
+    pub fn new(bracket: Token, elements: Vec<Box<Expr>>) -> Self {
+        Self { bracket, elements }
+    }
+}
+
+impl Map {
+    pub fn new(brace: Token, entries: Vec<(Box<Expr>, Box<Expr>)>) -> Self {
+        Self { brace, entries }
+    }
+}
+
+impl Index {
+    pub fn new(object: Box<Expr>, bracket: Token, index: Box<Expr>) -> Self {
+        Self {
+            object,
+            bracket,
+            index,
+        }
+    }
+}
+
+impl IndexSet {
+    pub fn new(object: Box<Expr>, bracket: Token, index: Box<Expr>, value: Box<Expr>) -> Self {
+        Self {
+            object,
+            bracket,
+            index,
+            value,
+        }
+    }
+}
+
+impl Destructure {
+    pub fn new(pattern: Pattern, value: Box<Expr>) -> Self {
+        Self { pattern, value }
+    }
+}
+
+impl Set {
+    pub fn new(object: Box<Expr>, name: Token, value: Box<Expr>) -> Self {
+        Self {
+            object,
+            name,
+            value,
+        }
+    }
+}
+
+impl This {
+    pub fn new(keyword: Token) -> Self {
+        Self { keyword }
+    }
+}
+
+impl Super {
+    pub fn new(keyword: Token, method: Token) -> Self {
+        Self { keyword, method }
+    }
+}
+
 impl<R> ExprAccept<R> for Expr {
     fn accept<V: ExprVisitor<R>>(&self, visitor: &mut V) -> R {
         match self {
@@ -147,6 +296,15 @@ impl<R> ExprAccept<R> for Expr {
             Expr::AssignmentExpr(a) => visitor.visit_assignment(a),
             Expr::LogicalExpr(l) => visitor.visit_logical(l),
             Expr::CallExpr(c) => visitor.visit_call(c),
+            Expr::GetExpr(g) => visitor.visit_get(g),
+            Expr::ListExpr(l) => visitor.visit_list(l),
+            Expr::MapExpr(m) => visitor.visit_map(m),
+            Expr::IndexExpr(i) => visitor.visit_index(i),
+            Expr::IndexSetExpr(i) => visitor.visit_index_set(i),
+            Expr::DestructureExpr(d) => visitor.visit_destructure(d),
+            Expr::SetExpr(s) => visitor.visit_set(s),
+            Expr::ThisExpr(t) => visitor.visit_this(t),
+            Expr::SuperExpr(s) => visitor.visit_super(s),
         }
     }
 }
@@ -186,26 +344,83 @@ pub mod ast_printer {
             self.parenthesize(&unary.operator.lexeme, vec![unary.right.as_ref()])
         }
         fn visit_literal(&mut self, literal: &Literal) -> String {
-            String::from(format!("{:?}", literal.value))
+            format!("{:?}", literal.value)
         }
         fn visit_grouping(&mut self, grouping: &Grouping) -> String {
             self.parenthesize("group", vec![grouping.expression.as_ref()])
         }
-        fn visit_var(&mut self, _var: &Var) -> String {
-            // String::from(format!("{:?}", var.name.lexeme))
-            todo!()
+        fn visit_var(&mut self, var: &Var) -> String {
+            var.name.lexeme.clone()
+        }
+
+        fn visit_assignment(&mut self, assignment: &Assignment) -> String {
+            let value = assignment.value.accept(self);
+            format!("(= {} {value})", assignment.name.lexeme)
+        }
+
+        fn visit_logical(&mut self, logical: &Logical) -> String {
+            self.parenthesize(
+                &logical.operator.lexeme,
+                vec![logical.left.as_ref(), logical.right.as_ref()],
+            )
+        }
+
+        fn visit_call(&mut self, call: &Call) -> String {
+            let mut exprs = vec![call.callee.as_ref()];
+            exprs.extend(call.arguments.iter().map(|arg| arg.as_ref()));
+            self.parenthesize("call", exprs)
+        }
+
+        fn visit_get(&mut self, get: &Get) -> String {
+            let object = get.object.accept(self);
+            format!("(. {object} {})", get.name.lexeme)
         }
 
-        fn visit_assignment(&mut self, _assignment: &Assignment) -> String {
-            todo!()
+        fn visit_list(&mut self, list: &List) -> String {
+            self.parenthesize("list", list.elements.iter().map(|e| e.as_ref()).collect())
         }
 
-        fn visit_logical(&mut self, _logical: &Logical) -> String {
-            todo!()
+        fn visit_map(&mut self, map: &Map) -> String {
+            let exprs = map
+                .entries
+                .iter()
+                .flat_map(|(key, value)| [key.as_ref(), value.as_ref()])
+                .collect();
+            self.parenthesize("map", exprs)
         }
 
-        fn visit_call(&mut self, _call: &Call) -> String {
-            todo!()
+        fn visit_index(&mut self, index: &Index) -> String {
+            self.parenthesize("index", vec![index.object.as_ref(), index.index.as_ref()])
+        }
+
+        fn visit_index_set(&mut self, index_set: &IndexSet) -> String {
+            self.parenthesize(
+                "index=",
+                vec![
+                    index_set.object.as_ref(),
+                    index_set.index.as_ref(),
+                    index_set.value.as_ref(),
+                ],
+            )
+        }
+
+        fn visit_destructure(&mut self, destructure: &Destructure) -> String {
+            let value = destructure.value.accept(self);
+            format!("(= {} {value})", Self::pattern(&destructure.pattern))
+        }
+
+        fn visit_set(&mut self, set: &Set) -> String {
+            let object = set.object.accept(self);
+            let value = set.value.accept(self);
+            format!("(= (. {object} {}) {value})", set.name.lexeme)
+        }
+
+        fn visit_this(&mut self, _this: &This) -> String {
+            String::from("this")
+        }
+
+        fn visit_super(&mut self, super_expr: &Super) -> String {
+            format!("(super {})", super_expr.method.lexeme)
         }
     }
     impl AstPrinter {
@@ -213,15 +428,28 @@ pub mod ast_printer {
             let s = expr.accept(self);
             println!("{s}");
         }
+        fn pattern(pattern: &Pattern) -> String {
+            match pattern {
+                Pattern::Name(name) => name.lexeme.clone(),
+                Pattern::List(_, elements) => {
+                    let elements: Vec<String> = elements.iter().map(Self::pattern).collect();
+                    format!("[{}]", elements.join(" "))
+                }
+                Pattern::Map(_, names) => {
+                    let names: Vec<&str> = names.iter().map(|n| n.lexeme.as_str()).collect();
+                    format!("{{{}}}", names.join(" "))
+                }
+            }
+        }
         fn parenthesize(&mut self, name: &str, exprs: Vec<&Expr>) -> String {
             let mut expr_s = String::new();
-            expr_s.push_str("(");
+            expr_s.push('(');
             expr_s.push_str(name);
             for expr in exprs {
-                expr_s.push_str(" ");
+                expr_s.push(' ');
                 expr_s.push_str(&expr.accept(self));
             }
-            expr_s.push_str(")");
+            expr_s.push(')');
             expr_s
         }
     }
@@ -0,0 +1,123 @@
+use std::cell::RefCell;
+use std::fs;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
+
+use crate::callable::*;
+use crate::interpreter::*;
+use crate::native::{bool, nil, string};
+use crate::token::LiteralValue;
+
+// Scripts can only reach files under the root given with `--fs-root`; with no
+// root every file function fails. Paths are relative to the root.
+pub fn define(interpreter: &mut Interpreter) {
+    interpreter.define_native("readFile", 1, |interpreter, arguments| {
+        let path = resolve(interpreter, &arguments[0], "readFile")?;
+        fs::read_to_string(&path)
+            .map(string)
+            .map_err(|e| io_error("read", &arguments[0], e))
+    });
+    interpreter.define_native("writeFile", 2, |interpreter, arguments| {
+        let path = resolve(interpreter, &arguments[0], "writeFile")?;
+        let contents = contents_arg(&arguments[1], "writeFile")?;
+        fs::write(&path, contents).map_err(|e| io_error("write", &arguments[0], e))?;
+        Ok(nil())
+    });
+    interpreter.define_native("appendFile", 2, |interpreter, arguments| {
+        let path = resolve(interpreter, &arguments[0], "appendFile")?;
+        let contents = contents_arg(&arguments[1], "appendFile")?;
+        fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(contents.as_bytes()))
+            .map_err(|e| io_error("append to", &arguments[0], e))?;
+        Ok(nil())
+    });
+    interpreter.define_native("exists", 1, |interpreter, arguments| {
+        let path = resolve(interpreter, &arguments[0], "exists")?;
+        Ok(bool(path.exists()))
+    });
+    interpreter.define_native("listDir", 1, |interpreter, arguments| {
+        let path = resolve(interpreter, &arguments[0], "listDir")?;
+        let mut names = fs::read_dir(&path)
+            .and_then(|entries| {
+                entries
+                    .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+                    .collect::<Result<Vec<_>, std::io::Error>>()
+            })
+            .map_err(|e| io_error("list", &arguments[0], e))?;
+        names.sort();
+        Ok(CallableRet::List(Rc::new(RefCell::new(
+            names.into_iter().map(string).collect(),
+        ))))
+    });
+    interpreter.define_native("removeFile", 1, |interpreter, arguments| {
+        let path = resolve(interpreter, &arguments[0], "removeFile")?;
+        fs::remove_file(&path).map_err(|e| io_error("remove", &arguments[0], e))?;
+        Ok(nil())
+    });
+}
+
+// `..` is resolved before the check, and the deepest part of the path that
+// already exists is canonicalized so a symlink can't lead outside the root.
+fn resolve(
+    interpreter: &Interpreter,
+    path: &CallableRet,
+    function: &str,
+) -> Result<PathBuf, RuntimeException> {
+    let CallableRet::Value(LiteralValue::StringLiteral(path)) = path else {
+        return Err(RuntimeException::InvalidArgument(format!(
+            "Path given to {function}() must be a string."
+        )));
+    };
+    let Some(root) = &interpreter.fs_root else {
+        return Err(RuntimeException::IoError(String::from(
+            "File access is disabled; run with --fs-root <dir> to allow it.",
+        )));
+    };
+    let mut resolved = PathBuf::new();
+    for component in root.join(path).components() {
+        match component {
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            Component::CurDir => {}
+            component => resolved.push(component),
+        }
+    }
+    let inside = resolved.starts_with(root)
+        && resolved
+            .ancestors()
+            .find(|ancestor| ancestor.symlink_metadata().is_ok())
+            .and_then(|existing| existing.canonicalize().ok())
+            .is_some_and(|existing| existing.starts_with(root));
+    if !inside {
+        return Err(RuntimeException::IoError(format!(
+            "Path '{path}' is outside the allowed root."
+        )));
+    }
+    Ok(resolved)
+}
+
+fn contents_arg<'a>(
+    argument: &'a CallableRet,
+    function: &str,
+) -> Result<&'a str, RuntimeException> {
+    match argument {
+        CallableRet::Value(LiteralValue::StringLiteral(s)) => Ok(s),
+        _ => Err(RuntimeException::InvalidArgument(format!(
+            "Contents given to {function}() must be a string."
+        ))),
+    }
+}
+
+fn io_error(action: &str, path: &CallableRet, error: std::io::Error) -> RuntimeException {
+    RuntimeException::IoError(format!("Can't {action} '{path}': {error}."))
+}
+
+pub fn canonical_root(dir: &str) -> Option<PathBuf> {
+    let root = Path::new(dir).canonicalize().ok()?;
+    root.is_dir().then_some(root)
+}
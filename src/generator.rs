@@ -0,0 +1,254 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::callable::CallableRet;
+use crate::environment::*;
+use crate::interpreter::*;
+use crate::iterator::LoxIterator;
+use crate::statement::*;
+use crate::token::LiteralValue;
+
+// `yield` is only allowed in statement position, so a generator only has to
+// unroll the statements that can contain one (blocks, `if`, loops) onto its
+// own frame stack; everything else is handed to the interpreter as a whole.
+pub struct Generator {
+    pub name: String,
+    frames: Vec<Frame>,
+    resume_target: Option<(YieldTarget, Rc<RefCell<Environment>>)>,
+    state: GeneratorState,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum GeneratorState {
+    Suspended,
+    Running,
+    Done,
+}
+
+enum Frame {
+    Block {
+        block: BlockStmtInner,
+        next: usize,
+        env: Rc<RefCell<Environment>>,
+        // False for an `if` branch, which runs in the enclosing block's scope.
+        owns_env: bool,
+    },
+    Loop {
+        while_stmt: WhileStmtInner,
+        env: Rc<RefCell<Environment>>,
+    },
+    ForIn {
+        for_in: Box<ForInStmtInner>,
+        iterator: LoxIterator,
+        env: Rc<RefCell<Environment>>,
+    },
+}
+
+impl Generator {
+    pub fn new(name: &str, body: &BlockStmtInner, env: Environment) -> Self {
+        Self {
+            name: String::from(name),
+            frames: vec![Frame::Block {
+                block: body.clone(),
+                next: 0,
+                env: Rc::new(RefCell::new(env)),
+                owns_env: true,
+            }],
+            resume_target: None,
+            state: GeneratorState::Suspended,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.state == GeneratorState::Done
+    }
+
+    pub fn resume(
+        generator: &Rc<RefCell<Generator>>,
+        interpreter: &mut Interpreter,
+    ) -> Result<Option<CallableRet>, RuntimeException> {
+        Self::send(
+            generator,
+            interpreter,
+            CallableRet::Value(LiteralValue::NilLiteral),
+        )
+    }
+
+    // `sent` becomes the value of the `yield` the body is suspended at. Returns
+    // `None` once the body has finished. A runtime error also finishes the
+    // generator and surfaces at the call that resumed it.
+    pub fn send(
+        generator: &Rc<RefCell<Generator>>,
+        interpreter: &mut Interpreter,
+        sent: CallableRet,
+    ) -> Result<Option<CallableRet>, RuntimeException> {
+        let (mut frames, resume_target) = {
+            let mut generator = generator.borrow_mut();
+            match generator.state {
+                GeneratorState::Done => return Ok(None),
+                GeneratorState::Running => {
+                    return Err(RuntimeException::InvalidArgument(String::from(
+                        "Generator is already running.",
+                    )))
+                }
+                GeneratorState::Suspended => {}
+            }
+            generator.state = GeneratorState::Running;
+            (
+                std::mem::take(&mut generator.frames),
+                generator.resume_target.take(),
+            )
+        };
+        let bound = match resume_target {
+            Some((YieldTarget::Declare(name), env)) => {
+                env.borrow_mut().define(&name.lexeme, Some(sent));
+                Ok(())
+            }
+            Some((YieldTarget::Assign(name), env)) => {
+                env.borrow_mut().assign(&name, sent).map(|_| ())
+            }
+            None => Ok(()),
+        };
+        let mut resume_target = None;
+        let result = bound.and_then(|_| Self::run(&mut frames, &mut resume_target, interpreter));
+        let result = match result {
+            Err(e) => Self::unwind(&mut frames, interpreter, Err(e)).map(|_| None),
+            result => result,
+        };
+        let mut generator = generator.borrow_mut();
+        match result {
+            Ok(Some(_)) => {
+                generator.state = GeneratorState::Suspended;
+                generator.frames = frames;
+                generator.resume_target = resume_target;
+            }
+            _ => generator.state = GeneratorState::Done,
+        }
+        result
+    }
+
+    fn run(
+        frames: &mut Vec<Frame>,
+        resume_target: &mut Option<(YieldTarget, Rc<RefCell<Environment>>)>,
+        interpreter: &mut Interpreter,
+    ) -> Result<Option<CallableRet>, RuntimeException> {
+        while let Some(frame) = frames.last_mut() {
+            let (stmt, env) = match frame {
+                Frame::Block {
+                    block,
+                    next,
+                    env,
+                    owns_env,
+                } => match block.0.get(*next) {
+                    Some(stmt) => {
+                        *next += 1;
+                        (stmt.clone(), env.clone())
+                    }
+                    None => {
+                        let env = owns_env.then(|| env.clone());
+                        frames.pop();
+                        if let Some(env) = env {
+                            interpreter.run_deferred(&env, Ok(()))?;
+                        }
+                        continue;
+                    }
+                },
+                Frame::Loop { while_stmt, env } => {
+                    let condition = interpreter.evaluate_in(&while_stmt.condition, env.clone())?;
+                    if interpreter.is_true(&condition) {
+                        (while_stmt.body.clone(), env.clone())
+                    } else {
+                        frames.pop();
+                        continue;
+                    }
+                }
+                Frame::ForIn {
+                    for_in,
+                    iterator,
+                    env,
+                } => match iterator.next(interpreter)? {
+                    Some(value) => {
+                        let mut loop_env = Environment::new_with_enclosing(env);
+                        loop_env.define(&for_in.name.lexeme, Some(value));
+                        (for_in.body.clone(), Rc::new(RefCell::new(loop_env)))
+                    }
+                    None => {
+                        frames.pop();
+                        continue;
+                    }
+                },
+            };
+            match *stmt {
+                Stmt::YieldStmt(yield_stmt) => {
+                    let value = match yield_stmt.value {
+                        Some(value) => interpreter.evaluate_in(&value, env.clone())?,
+                        None => CallableRet::Value(LiteralValue::NilLiteral),
+                    };
+                    *resume_target = yield_stmt.target.map(|target| (target, env));
+                    return Ok(Some(value));
+                }
+                Stmt::ReturnStmt(return_stmt) => {
+                    if let Some(value) = return_stmt.value {
+                        interpreter.evaluate_in(&value, env)?;
+                    }
+                    Self::unwind(frames, interpreter, Ok(()))?;
+                }
+                Stmt::BlockStmt(block) => frames.push(Frame::Block {
+                    block,
+                    next: 0,
+                    env: Rc::new(RefCell::new(Environment::new_with_enclosing(&env))),
+                    owns_env: true,
+                }),
+                Stmt::IfStmt(branch) => {
+                    let condition = interpreter.evaluate_in(&branch.condition, env.clone())?;
+                    let taken = if interpreter.is_true(&condition) {
+                        Some(branch.then_branch)
+                    } else {
+                        branch.else_branch
+                    };
+                    if let Some(taken) = taken {
+                        frames.push(Frame::Block {
+                            block: BlockStmtInner(vec![taken]),
+                            next: 0,
+                            env,
+                            owns_env: false,
+                        });
+                    }
+                }
+                Stmt::WhileStmt(while_stmt) => frames.push(Frame::Loop { while_stmt, env }),
+                Stmt::ForInStmt(for_in) => {
+                    let iterable = interpreter.evaluate_in(&for_in.iterable, env.clone())?;
+                    let iterator = LoxIterator::new(interpreter, iterable, &for_in.keyword)?;
+                    frames.push(Frame::ForIn {
+                        for_in: Box::new(for_in),
+                        iterator,
+                        env,
+                    });
+                }
+                stmt => interpreter.execute_in(&stmt, env)?,
+            }
+        }
+        Ok(None)
+    }
+
+    // Pops every frame, running the deferred expressions of each block on the
+    // way out, innermost first. This only happens when the generator finishes
+    // or fails; one dropped while suspended never runs its pending defers.
+    fn unwind(
+        frames: &mut Vec<Frame>,
+        interpreter: &mut Interpreter,
+        mut result: Result<(), RuntimeException>,
+    ) -> Result<(), RuntimeException> {
+        while let Some(frame) = frames.pop() {
+            if let Frame::Block {
+                env,
+                owns_env: true,
+                ..
+            } = frame
+            {
+                result = interpreter.run_deferred(&env, result);
+            }
+        }
+        result
+    }
+}
@@ -0,0 +1,48 @@
+use std::io::{self, BufRead, Read, Write};
+
+use crate::callable::*;
+use crate::interpreter::*;
+use crate::native::{nil, string};
+
+// These read from `Interpreter::input` rather than the process's stdin, so an
+// embedder or a test can feed a script its input.
+pub fn define(interpreter: &mut Interpreter) {
+    interpreter.define_native("readLine", 0, |interpreter, _| read_line(interpreter));
+    interpreter.define_native("readAll", 0, |interpreter, _| {
+        let mut contents = String::new();
+        interpreter
+            .input
+            .read_to_string(&mut contents)
+            .map_err(input_error)?;
+        Ok(string(contents))
+    });
+    interpreter.define_native("input", 1, |interpreter, arguments| {
+        print!("{}", arguments[0]);
+        io::stdout().flush().map_err(input_error)?;
+        read_line(interpreter)
+    });
+}
+
+// The line comes back without its line ending, or nil at end of input.
+fn read_line(interpreter: &mut Interpreter) -> Result<CallableRet, RuntimeException> {
+    let mut line = String::new();
+    if interpreter
+        .input
+        .read_line(&mut line)
+        .map_err(input_error)?
+        == 0
+    {
+        return Ok(nil());
+    }
+    if line.ends_with('\n') {
+        line.pop();
+        if line.ends_with('\r') {
+            line.pop();
+        }
+    }
+    Ok(string(line))
+}
+
+fn input_error(error: io::Error) -> RuntimeException {
+    RuntimeException::IoError(format!("Can't read input: {error}."))
+}
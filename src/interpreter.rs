@@ -1,14 +1,54 @@
+use crate::builtins::*;
 use crate::callable::*;
+use crate::class::*;
+use crate::files;
+use crate::input;
+use crate::iterator::LoxIterator;
+use crate::json;
+use crate::math;
+use crate::native::{self, NativeFunction};
+use crate::pattern;
+use crate::random::{self, Rng};
+use crate::scheduler::Scheduler;
 use crate::statement::*;
+use crate::strings;
+use crate::system;
+use crate::time;
 use crate::token::*;
 use crate::{environment::*, expression::ExprAccept};
 use crate::{expression::*, statement::StmtAccept};
 use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
+use std::io::{self, BufRead, BufReader};
+use std::path::PathBuf;
 use std::rc::Rc;
 
+use regex::Regex;
+
+pub const OPERAND_MUST_BE_NUMBER: &str = "Operand must be a number.";
+pub const OPERANDS_MUST_BE_NUMBERS: &str = "Operands must be a number.";
+pub const OPERANDS_MUST_BE_NUMBERS_OR_STRINGS: &str =
+    "Operands must be two numbers or two strings.";
+pub const NOT_ITERABLE: &str = "Can only iterate over lists, maps, strings, ranges and iterators.";
+pub const NOT_INDEXABLE: &str = "Only lists and maps can be indexed.";
+pub const MAP_KEYS_MUST_BE_STRINGS: &str = "Map keys must be strings.";
+
 pub struct Interpreter {
+    pub globals: Rc<RefCell<Environment>>,
     pub environment: Rc<RefCell<Environment>>,
+    pub scheduler: Scheduler,
+    // Canonical directory the file natives are confined to; `None` disables
+    // them.
+    pub fs_root: Option<PathBuf>,
+    // Where `readLine` and friends read from; stdin unless replaced.
+    pub input: Box<dyn BufRead>,
+    // Patterns already compiled by `regex()`.
+    pub regexes: HashMap<String, Rc<Regex>>,
+    // Seeded from the OS unless the embedder replaces it, e.g. for `--seed`.
+    pub rng: Rng,
+    // Arguments given to the script after its filename.
+    pub args: Vec<String>,
 }
 
 pub enum RuntimeException {
@@ -17,6 +57,29 @@ pub enum RuntimeException {
     InvalidCallable(Token, String),
     UnmatchedArity(usize, usize),
     FunctionReturn(Option<CallableRet>),
+    TailCall(Box<TailCall>),
+    // Raised by `exit()`; unwinds the whole script.
+    Exit(i32),
+    // The scheduler ran out of tasks while these many still wait on `recv()`.
+    Deadlock(usize),
+    InvalidPropertyAccess(Token),
+    UndefinedProperty(Token),
+    NotIterable(Token),
+    InvalidIndex(Token, String),
+    // Natives and generators raise these without knowing where they were
+    // called from; `call_value` turns them into a `NativeError` at the call
+    // site.
+    InvalidArgument(String),
+    PatternMismatch(Token, String),
+    AssertionFailed(Token, String),
+    InvalidFieldAccess(Token),
+    InvalidSuperclass(Token),
+    TraitError(Token, String),
+    PrivateAccess(Token, String),
+    IoError(String),
+    NativeError(Token, String),
+    // A deferred expression failed while another error was unwinding.
+    DeferredError(Box<RuntimeException>, Box<RuntimeException>),
 }
 impl fmt::Display for RuntimeException {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -33,106 +96,713 @@ impl fmt::Display for RuntimeException {
             Self::UnmatchedArity(expected, got) => {
                 write!(f, "Expected {expected} arguments but got {got}.")
             }
-            Self::FunctionReturn(_) => {
-                todo!()
+            // The parser rejects `return` outside functions, so these only
+            // escape when an embedder executes a bare return statement.
+            Self::FunctionReturn(_) | Self::TailCall(_) => {
+                write!(f, "Can't return from top-level code.")
+            }
+            Self::Exit(code) => {
+                write!(f, "Script exited with code {code}.")
+            }
+            Self::Deadlock(blocked) => {
+                write!(
+                    f,
+                    "Deadlock: {blocked} task(s) waiting on recv() with no task left to send."
+                )
+            }
+            Self::InvalidPropertyAccess(name) => {
+                write!(f, "Only objects have properties.\n[line {}]", name.line)
+            }
+            Self::UndefinedProperty(name) => {
+                write!(
+                    f,
+                    "Undefined property '{}'.\n[line {}]",
+                    name.lexeme, name.line
+                )
+            }
+            Self::NotIterable(keyword) => {
+                write!(f, "{}\n[line {}]", NOT_ITERABLE, keyword.line)
+            }
+            Self::InvalidIndex(bracket, desc) => {
+                write!(f, "{}\n[line {}]", desc, bracket.line)
+            }
+            Self::InvalidArgument(desc) => {
+                write!(f, "{desc}")
+            }
+            Self::PatternMismatch(token, desc) => {
+                write!(f, "{}\n[line {}]", desc, token.line)
+            }
+            Self::InvalidFieldAccess(name) => {
+                write!(f, "Only instances have fields.\n[line {}]", name.line)
+            }
+            Self::InvalidSuperclass(name) => {
+                write!(f, "Superclass must be a class.\n[line {}]", name.line)
+            }
+            Self::TraitError(name, desc) => {
+                write!(f, "{}\n[line {}]", desc, name.line)
+            }
+            Self::IoError(desc) => {
+                write!(f, "{desc}")
+            }
+            Self::PrivateAccess(name, class) => {
+                write!(
+                    f,
+                    "Can't access private member '{}' of class '{}'.\n[line {}]",
+                    name.lexeme, class, name.line
+                )
+            }
+            Self::AssertionFailed(keyword, desc) => {
+                write!(f, "Assertion failed: {}\n[line {}]", desc, keyword.line)
+            }
+            Self::NativeError(paren, desc) => {
+                write!(f, "{desc}\n[line {}]", paren.line)
+            }
+            Self::DeferredError(error, deferred) => {
+                write!(f, "{error}\nError in deferred expression: {deferred}")
             }
         }
     }
 }
+impl RuntimeException {
+    // The status `exit()` asked for, even if a deferred expression failed
+    // on the way out.
+    pub fn exit_code(&self) -> Option<i32> {
+        match self {
+            Self::Exit(code) => Some(*code),
+            Self::DeferredError(error, _) => error.exit_code(),
+            _ => None,
+        }
+    }
+
+    // The line the error points at, when its message names one.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Self::InvalidOperand(_, _, line) => Some(*line),
+            Self::InvalidPropertyAccess(token)
+            | Self::UndefinedProperty(token)
+            | Self::NotIterable(token)
+            | Self::InvalidIndex(token, _)
+            | Self::PatternMismatch(token, _)
+            | Self::InvalidFieldAccess(token)
+            | Self::InvalidSuperclass(token)
+            | Self::TraitError(token, _)
+            | Self::NativeError(token, _)
+            | Self::PrivateAccess(token, _)
+            | Self::AssertionFailed(token, _) => Some(token.line),
+            _ => None,
+        }
+    }
+
+    // The error as displayed, without its trailing `[line N]`.
+    pub fn message(&self) -> String {
+        let text = self.to_string();
+        match self.line() {
+            Some(line) => text
+                .strip_suffix(&format!("\n[line {line}]"))
+                .map(String::from)
+                .unwrap_or(text),
+            None => text,
+        }
+    }
+}
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Interpreter {
     pub fn new() -> Self {
         let globals = Environment::new();
-        globals.borrow_mut().define(
-            "clock",
-            Some(CallableRet::Callable(Callable::Native(Clock))),
-        );
-        Interpreter {
+        let mut interpreter = Interpreter {
+            globals: globals.clone(),
             environment: globals,
-        }
+            scheduler: Scheduler::new(),
+            fs_root: None,
+            input: Box::new(BufReader::new(io::stdin())),
+            regexes: HashMap::new(),
+            rng: Rng::from_os(),
+            args: Vec::new(),
+        };
+        native::define(&mut interpreter);
+        math::define(&mut interpreter);
+        strings::define(&mut interpreter);
+        files::define(&mut interpreter);
+        input::define(&mut interpreter);
+        json::define(&mut interpreter);
+        pattern::define(&mut interpreter);
+        random::define(&mut interpreter);
+        time::define(&mut interpreter);
+        system::define(&mut interpreter);
+        interpreter
+    }
+
+    pub fn define_native(
+        &mut self,
+        name: &str,
+        arity: usize,
+        function: impl Fn(&mut Interpreter, &[CallableRet]) -> Result<CallableRet, RuntimeException>
+            + 'static,
+    ) {
+        let native = NativeFunction {
+            name: String::from(name),
+            arity,
+            function: Rc::new(function),
+        };
+        self.globals
+            .borrow_mut()
+            .define(name, Some(CallableRet::Callable(Callable::Native(native))));
     }
     pub fn evaluate(&mut self, expr: &Expr) -> Result<CallableRet, RuntimeException> {
         expr.accept(self)
     }
     pub fn interprete(&mut self, stmts: &Vec<Stmt>) -> Result<(), RuntimeException> {
+        let mut result = Ok(());
         for stmt in stmts {
-            self.execute(stmt)?
+            result = self.execute(stmt);
+            if result.is_err() {
+                break;
+            }
         }
-        Ok(())
+        if result.is_ok() {
+            result = Scheduler::run(self);
+        }
+        let globals = self.globals.clone();
+        self.run_deferred(&globals, result)
     }
 
     fn execute(&mut self, stmt: &Stmt) -> Result<(), RuntimeException> {
         stmt.accept(self)
     }
 
+    pub fn evaluate_in(
+        &mut self,
+        expr: &Expr,
+        env: Rc<RefCell<Environment>>,
+    ) -> Result<CallableRet, RuntimeException> {
+        let prev_env = std::mem::replace(&mut self.environment, env);
+        let result = self.evaluate(expr);
+        self.environment = prev_env;
+        result
+    }
+
+    pub fn execute_in(
+        &mut self,
+        stmt: &Stmt,
+        env: Rc<RefCell<Environment>>,
+    ) -> Result<(), RuntimeException> {
+        let prev_env = std::mem::replace(&mut self.environment, env);
+        let result = self.execute(stmt);
+        self.environment = prev_env;
+        result
+    }
+
     pub fn execute_block(
         &mut self,
         stmts: &BlockStmtInner,
         block_env: Environment,
     ) -> Result<(), RuntimeException> {
         let prev_env = self.environment.clone();
-        self.environment = Rc::new(RefCell::new(block_env));
+        let block_env = Rc::new(RefCell::new(block_env));
+        self.environment = block_env.clone();
+        let mut result = Ok(());
         for stmt in &stmts.0 {
-            match self.execute(stmt.as_ref()) {
-                Ok(_) => continue,
-                Err(e) => {
-                    self.environment = prev_env;
-                    return Err(e);
-                }
+            result = self.execute(stmt.as_ref());
+            if result.is_err() {
+                break;
             }
         }
         self.environment = prev_env;
+        self.run_deferred(&block_env, result)
+    }
+
+    // Every deferred expression runs even if an earlier one fails. The first
+    // failure replaces a normal exit or a `return`; a failure during another
+    // error is attached to it so neither is lost.
+    pub fn run_deferred(
+        &mut self,
+        env: &Rc<RefCell<Environment>>,
+        mut result: Result<(), RuntimeException>,
+    ) -> Result<(), RuntimeException> {
+        let deferred = std::mem::take(&mut env.borrow_mut().deferred);
+        if deferred.is_empty() {
+            return result;
+        }
+        // `return f(x);` must call f before the cleanup runs, so a block with
+        // deferred expressions gives up the tail call.
+        if let Err(RuntimeException::TailCall(tail_call)) = result {
+            let TailCall {
+                callee,
+                arguments,
+                paren,
+            } = *tail_call;
+            result = self
+                .call_value(callee, arguments, &paren)
+                .and_then(|value| Err(RuntimeException::FunctionReturn(Some(value))));
+        }
+        for expr in deferred.iter().rev() {
+            if let Err(e) = self.evaluate_in(expr, env.clone()) {
+                match result {
+                    Ok(_)
+                    | Err(RuntimeException::FunctionReturn(_))
+                    | Err(RuntimeException::TailCall(_)) => result = Err(e),
+                    Err(_) if matches!(e, RuntimeException::Exit(_)) => result = Err(e),
+                    Err(error) => {
+                        result = Err(RuntimeException::DeferredError(
+                            Box::new(error),
+                            Box::new(e),
+                        ))
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    pub fn call_value(
+        &mut self,
+        callee: CallableRet,
+        arguments: Vec<CallableRet>,
+        paren: &Token,
+    ) -> Result<CallableRet, RuntimeException> {
+        match callee {
+            CallableRet::Callable(mut function) => {
+                if arguments.len() != function.arity() {
+                    Err(RuntimeException::UnmatchedArity(
+                        function.arity(),
+                        arguments.len(),
+                    ))
+                } else {
+                    function.call(self, &arguments).map_err(|e| match e {
+                        RuntimeException::InvalidArgument(desc)
+                        | RuntimeException::IoError(desc) => {
+                            RuntimeException::NativeError(paren.clone(), desc)
+                        }
+                        e => e,
+                    })
+                }
+            }
+            _ => Err(RuntimeException::InvalidCallable(
+                paren.clone(),
+                String::from("Can only call functions and classes"),
+            )),
+        }
+    }
+
+    // Decorators are evaluated top-down and applied bottom-up, like Python's.
+    pub fn decorate(
+        &mut self,
+        mut value: CallableRet,
+        decorators: &[CallableRet],
+        name: &Token,
+    ) -> Result<CallableRet, RuntimeException> {
+        for decorator in decorators.iter().rev() {
+            value = self.call_value(decorator.clone(), vec![value], name)?;
+        }
+        Ok(value)
+    }
+
+    pub fn enclosing_class(&self) -> Option<Rc<LoxClass>> {
+        self.environment.borrow().class()
+    }
+
+    pub fn bind_method(
+        &mut self,
+        method: &FunctionInner,
+        this: CallableRet,
+    ) -> Result<CallableRet, RuntimeException> {
+        let bound = CallableRet::Callable(Callable::Function(method.bind(this)));
+        self.decorate(bound, &method.decorators, &method.declaration.name)
+    }
+
+    fn evaluate_decorators(
+        &mut self,
+        function: &FunctionStmtInner,
+    ) -> Result<Vec<CallableRet>, RuntimeException> {
+        function
+            .decorators
+            .iter()
+            .map(|decorator| self.evaluate(decorator))
+            .collect()
+    }
+
+    fn members(
+        &mut self,
+        functions: &[FunctionStmtInner],
+        closure: &Rc<RefCell<Environment>>,
+        initializer: Option<&str>,
+    ) -> Result<HashMap<String, FunctionInner>, RuntimeException> {
+        let mut members = HashMap::new();
+        for function in functions {
+            let is_initializer = Some(function.name.lexeme.as_str()) == initializer;
+            let mut member = FunctionInner::new(function, closure.clone(), is_initializer);
+            member.decorators = Rc::new(self.evaluate_decorators(function)?);
+            members.insert(function.name.lexeme.clone(), member);
+        }
+        Ok(members)
+    }
+
+    pub fn get_property(
+        &mut self,
+        object: CallableRet,
+        name: &Token,
+    ) -> Result<CallableRet, RuntimeException> {
+        match object {
+            CallableRet::Generator(generator) => {
+                let method = match name.lexeme.as_str() {
+                    "next" => GeneratorMethod::Next,
+                    "send" => GeneratorMethod::Send,
+                    "done" => GeneratorMethod::Done,
+                    _ => return Err(RuntimeException::UndefinedProperty(name.clone())),
+                };
+                Ok(CallableRet::Callable(Callable::GeneratorMethod(
+                    generator, method,
+                )))
+            }
+            CallableRet::Channel(channel) => {
+                let method = match name.lexeme.as_str() {
+                    "send" => ChannelMethod::Send,
+                    "recv" => ChannelMethod::Recv,
+                    _ => return Err(RuntimeException::UndefinedProperty(name.clone())),
+                };
+                Ok(CallableRet::Callable(Callable::ChannelMethod(
+                    channel, method,
+                )))
+            }
+            CallableRet::Time(time) => time.get(name),
+            CallableRet::Regex(regex) => pattern::method(&regex, &name.lexeme)
+                .ok_or_else(|| RuntimeException::UndefinedProperty(name.clone())),
+            CallableRet::Instance(instance) => Instance::get(&instance, name, self),
+            CallableRet::Callable(Callable::Class(class)) => LoxClass::get(&class, name, self),
+            CallableRet::Enum(lox_enum) => {
+                if name.lexeme == "values" {
+                    return Ok(CallableRet::Callable(Callable::EnumValues(lox_enum)));
+                }
+                match lox_enum.variants.iter().position(|v| *v == name.lexeme) {
+                    Some(i) => Ok(CallableRet::EnumVariant(lox_enum, i)),
+                    None => Err(RuntimeException::UndefinedProperty(name.clone())),
+                }
+            }
+            CallableRet::Value(LiteralValue::StringLiteral(_))
+            | CallableRet::Value(LiteralValue::NumberLiteral(_))
+            | CallableRet::List(_)
+            | CallableRet::Map(_) => match BuiltinMethod::lookup(&object, &name.lexeme) {
+                Some(method) => Ok(CallableRet::Callable(Callable::BoundNative(Box::new(
+                    BoundNative {
+                        receiver: object,
+                        method,
+                        name: name.clone(),
+                    },
+                )))),
+                None => Err(RuntimeException::UndefinedProperty(name.clone())),
+            },
+            _ => Err(RuntimeException::InvalidPropertyAccess(name.clone())),
+        }
+    }
+
+    fn list_index(
+        &self,
+        list: &[CallableRet],
+        index: &CallableRet,
+        bracket: &Token,
+    ) -> Result<usize, RuntimeException> {
+        match index {
+            CallableRet::Value(LiteralValue::NumberLiteral(i)) if i.fract() == 0.0 => {
+                if *i >= 0.0 && (*i as usize) < list.len() {
+                    Ok(*i as usize)
+                } else {
+                    Err(RuntimeException::InvalidIndex(
+                        bracket.clone(),
+                        String::from("List index out of range."),
+                    ))
+                }
+            }
+            _ => Err(RuntimeException::InvalidIndex(
+                bracket.clone(),
+                String::from("List index must be an integer."),
+            )),
+        }
+    }
+
+    fn map_key(&self, key: CallableRet, bracket: &Token) -> Result<String, RuntimeException> {
+        match key {
+            CallableRet::Value(LiteralValue::StringLiteral(key)) => Ok(key),
+            _ => Err(RuntimeException::InvalidIndex(
+                bracket.clone(),
+                String::from(MAP_KEYS_MUST_BE_STRINGS),
+            )),
+        }
+    }
+
+    fn tail_call(expr: &Expr) -> Option<&Call> {
+        match expr {
+            Expr::CallExpr(call) => Some(call),
+            Expr::GroupingExpr(grouping) => Self::tail_call(&grouping.expression),
+            _ => None,
+        }
+    }
+
+    fn destructure(
+        &mut self,
+        pattern: &Pattern,
+        value: CallableRet,
+        bindings: &mut Vec<(Token, CallableRet)>,
+    ) -> Result<(), RuntimeException> {
+        match pattern {
+            Pattern::Name(name) => bindings.push((name.clone(), value)),
+            Pattern::List(bracket, patterns) => {
+                let list = match value {
+                    CallableRet::List(list) => list,
+                    _ => {
+                        return Err(RuntimeException::PatternMismatch(
+                            bracket.clone(),
+                            String::from("Can only destructure a list with a list pattern."),
+                        ))
+                    }
+                };
+                let elements = list.borrow().clone();
+                if elements.len() != patterns.len() {
+                    return Err(RuntimeException::PatternMismatch(
+                        bracket.clone(),
+                        format!(
+                            "Expected {} elements but got {}.",
+                            patterns.len(),
+                            elements.len()
+                        ),
+                    ));
+                }
+                for (pattern, element) in patterns.iter().zip(elements) {
+                    self.destructure(pattern, element, bindings)?;
+                }
+            }
+            // Instances are destructured through property access, so getters
+            // and private-member checks apply as usual.
+            Pattern::Map(_, names) if matches!(value, CallableRet::Instance(_)) => {
+                for name in names {
+                    let field = self.get_property(value.clone(), name)?;
+                    bindings.push((name.clone(), field));
+                }
+            }
+            Pattern::Map(brace, names) => {
+                let map = match value {
+                    CallableRet::Map(map) => map,
+                    _ => {
+                        return Err(RuntimeException::PatternMismatch(
+                            brace.clone(),
+                            String::from(
+                                "Can only destructure a map or an instance with a map pattern.",
+                            ),
+                        ))
+                    }
+                };
+                for name in names {
+                    match map.borrow().get(&name.lexeme) {
+                        Some(value) => bindings.push((name.clone(), value.clone())),
+                        None => {
+                            return Err(RuntimeException::PatternMismatch(
+                                name.clone(),
+                                format!("Missing key '{}' in map.", name.lexeme),
+                            ))
+                        }
+                    }
+                }
+            }
+        }
         Ok(())
     }
 
-    fn is_true(&self, literal_value: &CallableRet) -> bool {
+    pub fn is_true(&self, literal_value: &CallableRet) -> bool {
         match literal_value {
             CallableRet::Value(LiteralValue::NumberLiteral(_))
             | CallableRet::Value(LiteralValue::StringLiteral(_)) => true,
             CallableRet::Value(LiteralValue::BoolLiteral(b)) => *b,
             CallableRet::Value(LiteralValue::NilLiteral) => false,
-            CallableRet::Callable(_) => unimplemented!("trusty of callable unimplemented!"),
+            CallableRet::Generator(_)
+            | CallableRet::List(_)
+            | CallableRet::Map(_)
+            | CallableRet::Range(_, _)
+            | CallableRet::Channel(_)
+            | CallableRet::Regex(_)
+            | CallableRet::Time(_)
+            | CallableRet::Await(_)
+            | CallableRet::Enum(_)
+            | CallableRet::EnumVariant(_, _)
+            | CallableRet::Instance(_)
+            | CallableRet::Trait(_)
+            | CallableRet::Callable(_) => true,
         }
     }
 
-    fn is_equal(&mut self, l: &CallableRet, r: &CallableRet) -> bool {
-        if matches!(l, CallableRet::Value(LiteralValue::NilLiteral)) {
-            if matches!(r, CallableRet::Value(LiteralValue::NilLiteral)) {
-                true
-            } else {
-                false
+    pub fn is_equal(&mut self, l: &CallableRet, r: &CallableRet) -> bool {
+        match l {
+            CallableRet::Value(LiteralValue::NilLiteral) => {
+                matches!(r, CallableRet::Value(LiteralValue::NilLiteral))
             }
-        } else {
-            match l {
-                CallableRet::Value(LiteralValue::NumberLiteral(l)) => match r {
-                    CallableRet::Value(LiteralValue::NumberLiteral(r)) => l == r,
-                    _ => false,
-                },
-                CallableRet::Value(LiteralValue::BoolLiteral(l)) => match r {
-                    CallableRet::Value(LiteralValue::BoolLiteral(r)) => l == r,
-                    _ => false,
-                },
-                CallableRet::Value(LiteralValue::StringLiteral(l)) => match r {
-                    CallableRet::Value(LiteralValue::StringLiteral(r)) => l == r,
-                    _ => false,
-                },
-                _ => unreachable!(),
+            CallableRet::Value(LiteralValue::NumberLiteral(l)) => match r {
+                CallableRet::Value(LiteralValue::NumberLiteral(r)) => l == r,
+                _ => false,
+            },
+            CallableRet::Value(LiteralValue::BoolLiteral(l)) => match r {
+                CallableRet::Value(LiteralValue::BoolLiteral(r)) => l == r,
+                _ => false,
+            },
+            CallableRet::Value(LiteralValue::StringLiteral(l)) => match r {
+                CallableRet::Value(LiteralValue::StringLiteral(r)) => l == r,
+                _ => false,
+            },
+            CallableRet::Generator(l) => match r {
+                CallableRet::Generator(r) => Rc::ptr_eq(l, r),
+                _ => false,
+            },
+            CallableRet::List(l) => match r {
+                CallableRet::List(r) => Rc::ptr_eq(l, r),
+                _ => false,
+            },
+            CallableRet::Map(l) => match r {
+                CallableRet::Map(r) => Rc::ptr_eq(l, r),
+                _ => false,
+            },
+            CallableRet::Range(l_start, l_end) => match r {
+                CallableRet::Range(r_start, r_end) => l_start == r_start && l_end == r_end,
+                _ => false,
+            },
+            CallableRet::Channel(l) => match r {
+                CallableRet::Channel(r) => Rc::ptr_eq(l, r),
+                _ => false,
+            },
+            CallableRet::Regex(l) => match r {
+                CallableRet::Regex(r) => Rc::ptr_eq(l, r),
+                _ => false,
+            },
+            CallableRet::Time(l) => match r {
+                CallableRet::Time(r) => l.seconds == r.seconds,
+                _ => false,
+            },
+            CallableRet::Await(_) => false,
+            CallableRet::Instance(l) => match r {
+                CallableRet::Instance(r) => Rc::ptr_eq(l, r),
+                _ => false,
+            },
+            CallableRet::Trait(l) => match r {
+                CallableRet::Trait(r) => Rc::ptr_eq(l, r),
+                _ => false,
+            },
+            // Callables are equal only when they are the same function:
+            // each access to a method binds it afresh, so `a.m == a.m`
+            // is false, while a stored function equals itself.
+            CallableRet::Callable(l) => match (l, r) {
+                (Callable::Function(l), CallableRet::Callable(Callable::Function(r))) => {
+                    Rc::ptr_eq(&l.declaration, &r.declaration) && Rc::ptr_eq(&l.closure, &r.closure)
+                }
+                (Callable::Native(l), CallableRet::Callable(Callable::Native(r))) => {
+                    Rc::ptr_eq(&l.function, &r.function)
+                }
+                (Callable::Class(l), CallableRet::Callable(Callable::Class(r))) => Rc::ptr_eq(l, r),
+                _ => false,
+            },
+            CallableRet::Enum(l) => match r {
+                CallableRet::Enum(r) => Rc::ptr_eq(l, r),
+                _ => false,
+            },
+            CallableRet::EnumVariant(l, l_index) => match r {
+                CallableRet::EnumVariant(r, r_index) => Rc::ptr_eq(l, r) && l_index == r_index,
+                _ => false,
+            },
+        }
+    }
+
+    // Comparisons fall back to the right operand's mirrored method, so
+    // `1 < v` works when only `v` defines `__gt__`.
+    fn overloaded_binary(
+        &mut self,
+        operator: &Token,
+        left: &CallableRet,
+        right: &CallableRet,
+    ) -> Result<Option<CallableRet>, RuntimeException> {
+        if !matches!(left, CallableRet::Instance(_)) && !matches!(right, CallableRet::Instance(_)) {
+            return Ok(None);
+        }
+        let (name, mirrored) = match operator.ttype {
+            TokenType::PLUS => ("__add__", None),
+            TokenType::MINUS => ("__sub__", None),
+            TokenType::STAR => ("__mul__", None),
+            TokenType::SLASH => ("__div__", None),
+            TokenType::LESS => ("__lt__", Some("__gt__")),
+            TokenType::LESS_EQUAL => ("__le__", Some("__ge__")),
+            TokenType::GREATER => ("__gt__", Some("__lt__")),
+            TokenType::GREATER_EQUAL => ("__ge__", Some("__le__")),
+            _ => return Ok(None),
+        };
+        let mut result = self.call_operator(left, name, right, operator)?;
+        if let (None, Some(mirrored)) = (&result, mirrored) {
+            result = self.call_operator(right, mirrored, left, operator)?;
+        }
+        Ok(result)
+    }
+
+    // `==`, `!=` and `contains()` all compare through here, so `__eq__` is
+    // used consistently and is never asked about nil.
+    pub fn equals(
+        &mut self,
+        left: &CallableRet,
+        right: &CallableRet,
+        token: &Token,
+    ) -> Result<bool, RuntimeException> {
+        let is_nil =
+            |value: &CallableRet| matches!(value, CallableRet::Value(LiteralValue::NilLiteral));
+        if !is_nil(left) && !is_nil(right) {
+            let mut result = self.call_operator(left, "__eq__", right, token)?;
+            if result.is_none() {
+                result = self.call_operator(right, "__eq__", left, token)?;
+            }
+            if let Some(value) = result {
+                return Ok(self.is_true(&value));
             }
         }
+        Ok(self.is_equal(left, right))
     }
-}
-impl ExprVisitor<Result<CallableRet, RuntimeException>> for Interpreter {
-    fn visit_binary(&mut self, binary: &Binary) -> Result<CallableRet, RuntimeException> {
-        let left_val = self.evaluate(&binary.left)?;
-        let right_val = self.evaluate(&binary.right)?;
-        match binary.operator.ttype {
+
+    fn call_operator(
+        &mut self,
+        receiver: &CallableRet,
+        name: &str,
+        argument: &CallableRet,
+        operator: &Token,
+    ) -> Result<Option<CallableRet>, RuntimeException> {
+        let CallableRet::Instance(instance) = receiver else {
+            return Ok(None);
+        };
+        match Instance::method(instance, name, self)? {
+            Some(method) => Ok(Some(self.call_value(
+                method,
+                vec![argument.clone()],
+                operator,
+            )?)),
+            None => Ok(None),
+        }
+    }
+
+    fn binary_op(
+        &mut self,
+        operator: &Token,
+        left_val: CallableRet,
+        right_val: CallableRet,
+    ) -> Result<CallableRet, RuntimeException> {
+        if let Some(result) = self.overloaded_binary(operator, &left_val, &right_val)? {
+            return Ok(result);
+        }
+        if let Some(result) = time::binary(operator, &left_val, &right_val) {
+            return result;
+        }
+        match operator.ttype {
             TokenType::MINUS => {
                 let l = match left_val {
                     CallableRet::Value(LiteralValue::NumberLiteral(l)) => l,
                     _ => {
                         return Err(RuntimeException::InvalidOperand(
                             TokenType::MINUS,
-                            String::from("Operands must be a number."),
-                            binary.operator.line,
+                            String::from(OPERANDS_MUST_BE_NUMBERS),
+                            operator.line,
                         ))
                     }
                 };
@@ -141,49 +811,39 @@ impl ExprVisitor<Result<CallableRet, RuntimeException>> for Interpreter {
                     _ => {
                         return Err(RuntimeException::InvalidOperand(
                             TokenType::MINUS,
-                            String::from("Operands must be a number."),
-                            binary.operator.line,
+                            String::from(OPERANDS_MUST_BE_NUMBERS),
+                            operator.line,
                         ))
                     }
                 };
                 Ok(CallableRet::Value(LiteralValue::NumberLiteral(l - r)))
             }
             TokenType::PLUS => match left_val {
-                CallableRet::Value(LiteralValue::NumberLiteral(l)) => {
-                    match right_val {
-                        CallableRet::Value(LiteralValue::NumberLiteral(r)) => {
-                            return Ok(CallableRet::Value(LiteralValue::NumberLiteral(l + r)))
-                        }
-                        _ => {
-                            return Err(RuntimeException::InvalidOperand(
-                                TokenType::MINUS,
-                                String::from("Operands must be two numbers or two strings."),
-                                binary.operator.line,
-                            ))
-                        }
-                    };
-                }
-                CallableRet::Value(LiteralValue::StringLiteral(l)) => {
-                    match right_val {
-                        CallableRet::Value(LiteralValue::StringLiteral(r)) => {
-                            return Ok(CallableRet::Value(LiteralValue::StringLiteral(l + &r)))
-                        }
-                        _ => {
-                            return Err(RuntimeException::InvalidOperand(
-                                TokenType::MINUS,
-                                String::from("Operands must be two numbers or two strings."),
-                                binary.operator.line,
-                            ))
-                        }
-                    };
-                }
-                _ => {
-                    return Err(RuntimeException::InvalidOperand(
+                CallableRet::Value(LiteralValue::NumberLiteral(l)) => match right_val {
+                    CallableRet::Value(LiteralValue::NumberLiteral(r)) => {
+                        Ok(CallableRet::Value(LiteralValue::NumberLiteral(l + r)))
+                    }
+                    _ => Err(RuntimeException::InvalidOperand(
                         TokenType::MINUS,
-                        String::from("Operands must be two numbers or two strings."),
-                        binary.operator.line,
-                    ))
-                }
+                        String::from(OPERANDS_MUST_BE_NUMBERS_OR_STRINGS),
+                        operator.line,
+                    )),
+                },
+                CallableRet::Value(LiteralValue::StringLiteral(l)) => match right_val {
+                    CallableRet::Value(LiteralValue::StringLiteral(r)) => {
+                        Ok(CallableRet::Value(LiteralValue::StringLiteral(l + &r)))
+                    }
+                    _ => Err(RuntimeException::InvalidOperand(
+                        TokenType::MINUS,
+                        String::from(OPERANDS_MUST_BE_NUMBERS_OR_STRINGS),
+                        operator.line,
+                    )),
+                },
+                _ => Err(RuntimeException::InvalidOperand(
+                    TokenType::MINUS,
+                    String::from(OPERANDS_MUST_BE_NUMBERS_OR_STRINGS),
+                    operator.line,
+                )),
             },
             TokenType::STAR => {
                 let l = match left_val {
@@ -191,8 +851,8 @@ impl ExprVisitor<Result<CallableRet, RuntimeException>> for Interpreter {
                     _ => {
                         return Err(RuntimeException::InvalidOperand(
                             TokenType::MINUS,
-                            String::from("Operands must be a number."),
-                            binary.operator.line,
+                            String::from(OPERANDS_MUST_BE_NUMBERS),
+                            operator.line,
                         ))
                     }
                 };
@@ -201,8 +861,8 @@ impl ExprVisitor<Result<CallableRet, RuntimeException>> for Interpreter {
                     _ => {
                         return Err(RuntimeException::InvalidOperand(
                             TokenType::MINUS,
-                            String::from("Operands must be a number."),
-                            binary.operator.line,
+                            String::from(OPERANDS_MUST_BE_NUMBERS),
+                            operator.line,
                         ))
                     }
                 };
@@ -214,8 +874,8 @@ impl ExprVisitor<Result<CallableRet, RuntimeException>> for Interpreter {
                     _ => {
                         return Err(RuntimeException::InvalidOperand(
                             TokenType::MINUS,
-                            String::from("Operands must be a number."),
-                            binary.operator.line,
+                            String::from(OPERANDS_MUST_BE_NUMBERS),
+                            operator.line,
                         ))
                     }
                 };
@@ -224,8 +884,8 @@ impl ExprVisitor<Result<CallableRet, RuntimeException>> for Interpreter {
                     _ => {
                         return Err(RuntimeException::InvalidOperand(
                             TokenType::MINUS,
-                            String::from("Operands must be a number."),
-                            binary.operator.line,
+                            String::from(OPERANDS_MUST_BE_NUMBERS),
+                            operator.line,
                         ))
                     }
                 };
@@ -237,8 +897,8 @@ impl ExprVisitor<Result<CallableRet, RuntimeException>> for Interpreter {
                     _ => {
                         return Err(RuntimeException::InvalidOperand(
                             TokenType::MINUS,
-                            String::from("Operands must be a number."),
-                            binary.operator.line,
+                            String::from(OPERANDS_MUST_BE_NUMBERS),
+                            operator.line,
                         ))
                     }
                 };
@@ -247,8 +907,8 @@ impl ExprVisitor<Result<CallableRet, RuntimeException>> for Interpreter {
                     _ => {
                         return Err(RuntimeException::InvalidOperand(
                             TokenType::MINUS,
-                            String::from("Operands must be a number."),
-                            binary.operator.line,
+                            String::from(OPERANDS_MUST_BE_NUMBERS),
+                            operator.line,
                         ))
                     }
                 };
@@ -260,8 +920,8 @@ impl ExprVisitor<Result<CallableRet, RuntimeException>> for Interpreter {
                     _ => {
                         return Err(RuntimeException::InvalidOperand(
                             TokenType::MINUS,
-                            String::from("Operands must be a number."),
-                            binary.operator.line,
+                            String::from(OPERANDS_MUST_BE_NUMBERS),
+                            operator.line,
                         ))
                     }
                 };
@@ -270,8 +930,8 @@ impl ExprVisitor<Result<CallableRet, RuntimeException>> for Interpreter {
                     _ => {
                         return Err(RuntimeException::InvalidOperand(
                             TokenType::MINUS,
-                            String::from("Operands must be a number."),
-                            binary.operator.line,
+                            String::from(OPERANDS_MUST_BE_NUMBERS),
+                            operator.line,
                         ))
                     }
                 };
@@ -283,8 +943,8 @@ impl ExprVisitor<Result<CallableRet, RuntimeException>> for Interpreter {
                     _ => {
                         return Err(RuntimeException::InvalidOperand(
                             TokenType::MINUS,
-                            String::from("Operands must be a number."),
-                            binary.operator.line,
+                            String::from(OPERANDS_MUST_BE_NUMBERS),
+                            operator.line,
                         ))
                     }
                 };
@@ -293,8 +953,8 @@ impl ExprVisitor<Result<CallableRet, RuntimeException>> for Interpreter {
                     _ => {
                         return Err(RuntimeException::InvalidOperand(
                             TokenType::MINUS,
-                            String::from("Operands must be a number."),
-                            binary.operator.line,
+                            String::from(OPERANDS_MUST_BE_NUMBERS),
+                            operator.line,
                         ))
                     }
                 };
@@ -306,8 +966,8 @@ impl ExprVisitor<Result<CallableRet, RuntimeException>> for Interpreter {
                     _ => {
                         return Err(RuntimeException::InvalidOperand(
                             TokenType::MINUS,
-                            String::from("Operands must be a number."),
-                            binary.operator.line,
+                            String::from(OPERANDS_MUST_BE_NUMBERS),
+                            operator.line,
                         ))
                     }
                 };
@@ -316,22 +976,29 @@ impl ExprVisitor<Result<CallableRet, RuntimeException>> for Interpreter {
                     _ => {
                         return Err(RuntimeException::InvalidOperand(
                             TokenType::MINUS,
-                            String::from("Operands must be a number."),
-                            binary.operator.line,
+                            String::from(OPERANDS_MUST_BE_NUMBERS),
+                            operator.line,
                         ))
                     }
                 };
                 Ok(CallableRet::Value(LiteralValue::BoolLiteral(l <= r)))
             }
             TokenType::BANG_EQUAL => Ok(CallableRet::Value(LiteralValue::BoolLiteral(
-                !self.is_equal(&left_val, &right_val),
+                !self.equals(&left_val, &right_val, operator)?,
             ))),
             TokenType::EQUAL_EQUAL => Ok(CallableRet::Value(LiteralValue::BoolLiteral(
-                self.is_equal(&left_val, &right_val),
+                self.equals(&left_val, &right_val, operator)?,
             ))),
             _ => unimplemented!(),
         }
     }
+}
+impl ExprVisitor<Result<CallableRet, RuntimeException>> for Interpreter {
+    fn visit_binary(&mut self, binary: &Binary) -> Result<CallableRet, RuntimeException> {
+        let left_val = self.evaluate(&binary.left)?;
+        let right_val = self.evaluate(&binary.right)?;
+        self.binary_op(&binary.operator, left_val, right_val)
+    }
 
     fn visit_unary(&mut self, unary: &Unary) -> Result<CallableRet, RuntimeException> {
         let right_val = self.evaluate(&unary.right)?;
@@ -340,9 +1007,19 @@ impl ExprVisitor<Result<CallableRet, RuntimeException>> for Interpreter {
                 CallableRet::Value(LiteralValue::NumberLiteral(f)) => {
                     Ok(CallableRet::Value(LiteralValue::NumberLiteral(-f)))
                 }
+                CallableRet::Instance(instance) => {
+                    match Instance::method(&instance, "__neg__", self)? {
+                        Some(method) => self.call_value(method, Vec::new(), &unary.operator),
+                        None => Err(RuntimeException::InvalidOperand(
+                            TokenType::MINUS,
+                            String::from(OPERAND_MUST_BE_NUMBER),
+                            unary.operator.line,
+                        )),
+                    }
+                }
                 _ => Err(RuntimeException::InvalidOperand(
                     TokenType::MINUS,
-                    String::from("Operand must be a number."),
+                    String::from(OPERAND_MUST_BE_NUMBER),
                     unary.operator.line,
                 )),
             },
@@ -362,7 +1039,7 @@ impl ExprVisitor<Result<CallableRet, RuntimeException>> for Interpreter {
     }
 
     fn visit_var(&mut self, var: &Var) -> Result<CallableRet, RuntimeException> {
-        Ok(self.environment.borrow().get(&var.name)?)
+        self.environment.borrow().get(&var.name)
     }
 
     fn visit_assignment(
@@ -394,23 +1071,141 @@ impl ExprVisitor<Result<CallableRet, RuntimeException>> for Interpreter {
         for arg in &call.arguments {
             arguments.push(self.evaluate(arg.as_ref())?);
         }
-        match callee {
-            CallableRet::Callable(mut function) => {
-                if arguments.len() != function.arity() {
-                    Err(RuntimeException::UnmatchedArity(
-                        function.arity(),
-                        arguments.len(),
+        self.call_value(callee, arguments, &call.paren)
+    }
+
+    fn visit_get(&mut self, get: &Get) -> Result<CallableRet, RuntimeException> {
+        let object = self.evaluate(&get.object)?;
+        self.get_property(object, &get.name)
+    }
+
+    fn visit_list(&mut self, list: &List) -> Result<CallableRet, RuntimeException> {
+        let mut elements = Vec::new();
+        for element in &list.elements {
+            elements.push(self.evaluate(element)?);
+        }
+        Ok(CallableRet::List(Rc::new(RefCell::new(elements))))
+    }
+
+    fn visit_map(&mut self, map: &Map) -> Result<CallableRet, RuntimeException> {
+        let mut entries = BTreeMap::new();
+        for (key, value) in &map.entries {
+            let key = match self.evaluate(key)? {
+                CallableRet::Value(LiteralValue::StringLiteral(key)) => key,
+                _ => {
+                    return Err(RuntimeException::InvalidIndex(
+                        map.brace.clone(),
+                        String::from(MAP_KEYS_MUST_BE_STRINGS),
                     ))
-                } else {
-                    function.call(self, &arguments)
                 }
+            };
+            entries.insert(key, self.evaluate(value)?);
+        }
+        Ok(CallableRet::Map(Rc::new(RefCell::new(entries))))
+    }
+
+    fn visit_index(&mut self, index: &Index) -> Result<CallableRet, RuntimeException> {
+        let object = self.evaluate(&index.object)?;
+        let key = self.evaluate(&index.index)?;
+        match object {
+            CallableRet::List(list) => {
+                let i = self.list_index(&list.borrow(), &key, &index.bracket)?;
+                Ok(list.borrow()[i].clone())
             }
-            CallableRet::Value(_) => Err(RuntimeException::InvalidCallable(
-                call.paren.clone(),
-                String::from("Can only call functions and classes"),
+            CallableRet::Map(map) => {
+                let key = self.map_key(key, &index.bracket)?;
+                Ok(map
+                    .borrow()
+                    .get(&key)
+                    .cloned()
+                    .unwrap_or(CallableRet::Value(LiteralValue::NilLiteral)))
+            }
+            _ => Err(RuntimeException::InvalidIndex(
+                index.bracket.clone(),
+                String::from(NOT_INDEXABLE),
+            )),
+        }
+    }
+
+    fn visit_destructure(
+        &mut self,
+        destructure: &Destructure,
+    ) -> Result<CallableRet, RuntimeException> {
+        let value = self.evaluate(&destructure.value)?;
+        let mut bindings = Vec::new();
+        self.destructure(&destructure.pattern, value.clone(), &mut bindings)?;
+        for (name, value) in bindings {
+            RefCell::borrow_mut(&self.environment).assign(&name, value)?;
+        }
+        Ok(value)
+    }
+
+    fn visit_set(&mut self, set: &Set) -> Result<CallableRet, RuntimeException> {
+        match self.evaluate(&set.object)? {
+            CallableRet::Instance(instance) => {
+                let value = self.evaluate(&set.value)?;
+                Instance::set(&instance, &set.name, value.clone(), self)?;
+                Ok(value)
+            }
+            CallableRet::Callable(Callable::Class(class)) => {
+                let value = self.evaluate(&set.value)?;
+                LoxClass::set(&class, &set.name, value.clone(), self)?;
+                Ok(value)
+            }
+            _ => Err(RuntimeException::InvalidFieldAccess(set.name.clone())),
+        }
+    }
+
+    fn visit_this(&mut self, this: &This) -> Result<CallableRet, RuntimeException> {
+        self.environment.borrow().get(&this.keyword)
+    }
+
+    fn visit_super(&mut self, super_expr: &Super) -> Result<CallableRet, RuntimeException> {
+        let superclass = self.environment.borrow().get(&super_expr.keyword)?;
+        let mut this = super_expr.keyword.clone();
+        this.lexeme = String::from("this");
+        let this = self.environment.borrow().get(&this)?;
+        let CallableRet::Callable(Callable::Class(superclass)) = superclass else {
+            unreachable!()
+        };
+        let method = match &this {
+            CallableRet::Instance(_) => superclass.find(&super_expr.method.lexeme, |c| &c.methods),
+            _ => superclass.find(&super_expr.method.lexeme, |c| &c.class_methods),
+        };
+        match method {
+            Some((owner, method)) => {
+                owner.check_access(&super_expr.method, self)?;
+                let method = method.clone();
+                self.bind_method(&method, this)
+            }
+            None => Err(RuntimeException::UndefinedProperty(
+                super_expr.method.clone(),
             )),
         }
     }
+
+    fn visit_index_set(&mut self, index_set: &IndexSet) -> Result<CallableRet, RuntimeException> {
+        let object = self.evaluate(&index_set.object)?;
+        let key = self.evaluate(&index_set.index)?;
+        let value = self.evaluate(&index_set.value)?;
+        match object {
+            CallableRet::List(list) => {
+                let i = self.list_index(&list.borrow(), &key, &index_set.bracket)?;
+                list.borrow_mut()[i] = value.clone();
+            }
+            CallableRet::Map(map) => {
+                let key = self.map_key(key, &index_set.bracket)?;
+                map.borrow_mut().insert(key, value.clone());
+            }
+            _ => {
+                return Err(RuntimeException::InvalidIndex(
+                    index_set.bracket.clone(),
+                    String::from(NOT_INDEXABLE),
+                ))
+            }
+        }
+        Ok(value)
+    }
 }
 
 impl StmtVisitor<Result<(), RuntimeException>> for Interpreter {
@@ -421,10 +1216,8 @@ impl StmtVisitor<Result<(), RuntimeException>> for Interpreter {
 
     fn visit_print(&mut self, print: &PrintStmtInner) -> Result<(), RuntimeException> {
         let rst = self.evaluate(print.0.as_ref())?;
-        match rst {
-            CallableRet::Value(val) => Ok(println!("{val}")),
-            CallableRet::Callable(func) => Ok(println!("{func}")),
-        }
+        println!("{rst}");
+        Ok(())
     }
 
     fn visit_var(&mut self, var: &VarStmtInner) -> Result<(), RuntimeException> {
@@ -432,7 +1225,18 @@ impl StmtVisitor<Result<(), RuntimeException>> for Interpreter {
             Some(expr) => Some(self.evaluate(expr.as_ref())?),
             None => None,
         };
-        RefCell::borrow_mut(&self.environment).define(&var.0.lexeme, val);
+        match (&var.0, val) {
+            (Pattern::Name(name), val) => {
+                RefCell::borrow_mut(&self.environment).define(&name.lexeme, val)
+            }
+            (pattern, val) => {
+                let mut bindings = Vec::new();
+                self.destructure(pattern, val.unwrap(), &mut bindings)?;
+                for (name, value) in bindings {
+                    RefCell::borrow_mut(&self.environment).define(&name.lexeme, Some(value));
+                }
+            }
+        }
         Ok(())
     }
 
@@ -463,19 +1267,195 @@ impl StmtVisitor<Result<(), RuntimeException>> for Interpreter {
     }
 
     fn visit_function(&mut self, func_stmt: &FunctionStmtInner) -> Result<(), RuntimeException> {
-        let func = FunctionInner::new(func_stmt, self.environment.clone());
-        Ok(self.environment.borrow_mut().define(
-            &func_stmt.name.lexeme,
-            Some(CallableRet::Callable(Callable::Function(func))),
-        ))
+        let decorators = self.evaluate_decorators(func_stmt)?;
+        let func = FunctionInner::new(func_stmt, self.environment.clone(), false);
+        let func = self.decorate(
+            CallableRet::Callable(Callable::Function(func)),
+            &decorators,
+            &func_stmt.name,
+        )?;
+        self.environment
+            .borrow_mut()
+            .define(&func_stmt.name.lexeme, Some(func));
+        Ok(())
+    }
+
+    fn visit_class(&mut self, class: &ClassStmtInner) -> Result<(), RuntimeException> {
+        let superclass = match &class.superclass {
+            Some(superclass) => match self.environment.borrow().get(&superclass.name)? {
+                CallableRet::Callable(Callable::Class(superclass)) => Some(superclass),
+                _ => return Err(RuntimeException::InvalidSuperclass(superclass.name.clone())),
+            },
+            None => None,
+        };
+        let mut traits = Vec::new();
+        for lox_trait in &class.traits {
+            match self.environment.borrow().get(&lox_trait.name)? {
+                CallableRet::Trait(lox_trait) => traits.push(lox_trait),
+                _ => {
+                    return Err(RuntimeException::TraitError(
+                        lox_trait.name.clone(),
+                        String::from("Can only include traits."),
+                    ))
+                }
+            }
+        }
+        let mut env = Environment::new_with_enclosing(&self.environment);
+        if let Some(superclass) = &superclass {
+            env.define(
+                "super",
+                Some(CallableRet::Callable(Callable::Class(superclass.clone()))),
+            );
+        }
+        let closure = Rc::new(RefCell::new(env));
+        let mut class_value = LoxClass {
+            name: class.name.lexeme.clone(),
+            superclass,
+            methods: self.members(&class.methods, &closure, Some("init"))?,
+            getters: self.members(&class.getters, &closure, None)?,
+            setters: self.members(&class.setters, &closure, None)?,
+            class_methods: self.members(&class.class_methods, &closure, None)?,
+            statics: RefCell::default(),
+            bound: RefCell::default(),
+            private: class
+                .methods
+                .iter()
+                .chain(&class.getters)
+                .chain(&class.setters)
+                .chain(&class.class_methods)
+                .map(|member| &member.name)
+                .filter(|name| name.lexeme.starts_with('#'))
+                .chain(&class.private)
+                .map(|name| name.lexeme.clone())
+                .collect(),
+        };
+        LoxTrait::include(&traits, &mut class_value, &class.name)?;
+        let class_value = Rc::new(class_value);
+        closure.borrow_mut().class = Some(Rc::downgrade(&class_value));
+        self.environment.borrow_mut().define(
+            &class.name.lexeme,
+            Some(CallableRet::Callable(Callable::Class(class_value))),
+        );
+        Ok(())
+    }
+
+    fn visit_trait(&mut self, trait_stmt: &TraitStmtInner) -> Result<(), RuntimeException> {
+        let closure = self.environment.clone();
+        let methods = self.members(&trait_stmt.methods, &closure, Some("init"))?;
+        let lox_trait = LoxTrait {
+            name: trait_stmt.name.lexeme.clone(),
+            methods,
+            required: trait_stmt
+                .required
+                .iter()
+                .map(|required| required.lexeme.clone())
+                .collect(),
+        };
+        self.environment.borrow_mut().define(
+            &trait_stmt.name.lexeme,
+            Some(CallableRet::Trait(Rc::new(lox_trait))),
+        );
+        Ok(())
+    }
+
+    fn visit_enum(&mut self, enum_stmt: &EnumStmtInner) -> Result<(), RuntimeException> {
+        let lox_enum = LoxEnum {
+            name: enum_stmt.name.lexeme.clone(),
+            variants: enum_stmt
+                .variants
+                .iter()
+                .map(|variant| variant.lexeme.clone())
+                .collect(),
+        };
+        self.environment.borrow_mut().define(
+            &enum_stmt.name.lexeme,
+            Some(CallableRet::Enum(Rc::new(lox_enum))),
+        );
+        Ok(())
     }
 
     fn visit_return(&mut self, return_stmt: &ReturnStmtInner) -> Result<(), RuntimeException> {
         match &return_stmt.value {
-            Some(value) => Err(RuntimeException::FunctionReturn(Some(
-                self.evaluate(&value)?,
-            ))),
+            Some(value) => match Self::tail_call(value) {
+                Some(call) => {
+                    let callee = self.evaluate(&call.callee)?;
+                    let mut arguments = Vec::new();
+                    for arg in &call.arguments {
+                        arguments.push(self.evaluate(arg.as_ref())?);
+                    }
+                    Err(RuntimeException::TailCall(Box::new(TailCall {
+                        callee,
+                        arguments,
+                        paren: call.paren.clone(),
+                    })))
+                }
+                None => Err(RuntimeException::FunctionReturn(Some(
+                    self.evaluate(value)?,
+                ))),
+            },
             None => Err(RuntimeException::FunctionReturn(None)),
         }
     }
+
+    fn visit_yield(&mut self, _yield_stmt: &YieldStmtInner) -> Result<(), RuntimeException> {
+        unreachable!("yield is only executed by a resumed generator")
+    }
+
+    fn visit_for_in(&mut self, for_in: &ForInStmtInner) -> Result<(), RuntimeException> {
+        let iterable = self.evaluate(&for_in.iterable)?;
+        let mut iterator = LoxIterator::new(self, iterable, &for_in.keyword)?;
+        while let Some(value) = iterator.next(self)? {
+            let mut loop_env = Environment::new_with_enclosing(&self.environment);
+            loop_env.define(&for_in.name.lexeme, Some(value));
+            self.execute_in(&for_in.body, Rc::new(RefCell::new(loop_env)))?;
+        }
+        Ok(())
+    }
+
+    fn visit_defer(&mut self, defer: &DeferStmtInner) -> Result<(), RuntimeException> {
+        self.environment
+            .borrow_mut()
+            .deferred
+            .push(*defer.expr.clone());
+        Ok(())
+    }
+
+    fn visit_assert(&mut self, assert: &AssertStmtInner) -> Result<(), RuntimeException> {
+        let mut operands = None;
+        let result = match assert.condition.as_ref() {
+            Expr::BinaryExpr(binary)
+                if matches!(
+                    binary.operator.ttype,
+                    TokenType::EQUAL_EQUAL
+                        | TokenType::BANG_EQUAL
+                        | TokenType::LESS
+                        | TokenType::LESS_EQUAL
+                        | TokenType::GREATER
+                        | TokenType::GREATER_EQUAL
+                ) =>
+            {
+                let left = self.evaluate(&binary.left)?;
+                let right = self.evaluate(&binary.right)?;
+                let result = self.binary_op(&binary.operator, left.clone(), right.clone())?;
+                operands = Some((left, right));
+                result
+            }
+            condition => self.evaluate(condition)?,
+        };
+        if self.is_true(&result) {
+            return Ok(());
+        }
+        let mut desc = assert.source.clone();
+        if let Some(message) = &assert.message {
+            let message = self.evaluate(message)?;
+            desc.push_str(&format!(", {message}"));
+        }
+        if let Some((left, right)) = operands {
+            desc.push_str(&format!("\nleft: {}, right: {}", left.repr(), right.repr()));
+        }
+        Err(RuntimeException::AssertionFailed(
+            assert.keyword.clone(),
+            desc,
+        ))
+    }
 }
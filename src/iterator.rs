@@ -0,0 +1,105 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::callable::*;
+use crate::generator::Generator;
+use crate::interpreter::*;
+use crate::token::*;
+
+pub enum LoxIterator {
+    List(Rc<RefCell<Vec<CallableRet>>>, usize),
+    Keys(std::vec::IntoIter<String>),
+    Chars(std::vec::IntoIter<char>),
+    Range(f64, f64),
+    Generator(Rc<RefCell<Generator>>, Token),
+    Protocol(CallableRet, Token),
+}
+
+impl LoxIterator {
+    // Anything that isn't a built-in iterable can take part through the
+    // iterator protocol: `iter()` returns an object whose `next()` is called
+    // until it returns nil.
+    pub fn new(
+        interpreter: &mut Interpreter,
+        iterable: CallableRet,
+        keyword: &Token,
+    ) -> Result<Self, RuntimeException> {
+        match iterable {
+            CallableRet::List(list) => Ok(Self::List(list, 0)),
+            CallableRet::Map(map) => {
+                let keys: Vec<String> = map.borrow().keys().cloned().collect();
+                Ok(Self::Keys(keys.into_iter()))
+            }
+            CallableRet::Value(LiteralValue::StringLiteral(s)) => {
+                let chars: Vec<char> = s.chars().collect();
+                Ok(Self::Chars(chars.into_iter()))
+            }
+            CallableRet::Range(start, end) => Ok(Self::Range(start, end)),
+            CallableRet::Generator(generator) => Ok(Self::Generator(generator, keyword.clone())),
+            object => {
+                let not_iterable = |_| RuntimeException::NotIterable(keyword.clone());
+                let iter = interpreter
+                    .get_property(object, &Self::method_name("iter", keyword))
+                    .map_err(not_iterable)?;
+                let iterator = interpreter.call_value(iter, Vec::new(), keyword)?;
+                let next = interpreter
+                    .get_property(iterator, &Self::method_name("next", keyword))
+                    .map_err(not_iterable)?;
+                Ok(Self::Protocol(next, keyword.clone()))
+            }
+        }
+    }
+
+    pub fn next(
+        &mut self,
+        interpreter: &mut Interpreter,
+    ) -> Result<Option<CallableRet>, RuntimeException> {
+        match self {
+            Self::List(list, index) => {
+                let element = list.borrow().get(*index).cloned();
+                *index += 1;
+                Ok(element)
+            }
+            Self::Keys(keys) => Ok(keys
+                .next()
+                .map(|key| CallableRet::Value(LiteralValue::StringLiteral(key)))),
+            Self::Chars(chars) => Ok(chars
+                .next()
+                .map(|c| CallableRet::Value(LiteralValue::StringLiteral(String::from(c))))),
+            Self::Range(start, end) => {
+                if *start < *end {
+                    let current = *start;
+                    *start += 1.0;
+                    Ok(Some(CallableRet::Value(LiteralValue::NumberLiteral(
+                        current,
+                    ))))
+                } else {
+                    Ok(None)
+                }
+            }
+            Self::Generator(generator, keyword) => Generator::resume(generator, interpreter)
+                .map_err(|e| match e {
+                    RuntimeException::InvalidArgument(desc) => {
+                        RuntimeException::NativeError(keyword.clone(), desc)
+                    }
+                    e => e,
+                }),
+            Self::Protocol(next, keyword) => {
+                match interpreter.call_value(next.clone(), Vec::new(), keyword)? {
+                    CallableRet::Value(LiteralValue::NilLiteral) => Ok(None),
+                    value => Ok(Some(value)),
+                }
+            }
+        }
+    }
+
+    fn method_name(name: &str, keyword: &Token) -> Token {
+        Token::new(
+            TokenType::IDENTIFIER,
+            keyword.line,
+            keyword.offset,
+            String::from(name),
+            None,
+        )
+    }
+}
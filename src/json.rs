@@ -0,0 +1,399 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use crate::callable::*;
+use crate::interpreter::*;
+use crate::native::{bool, nil, number, string, string_arg};
+use crate::token::LiteralValue;
+
+// Deeper documents and values are rejected rather than risking the native
+// stack.
+const MAX_DEPTH: usize = 512;
+
+pub fn define(interpreter: &mut Interpreter) {
+    // Invalid JSON is a runtime error; `try(jsonParse, [text])` turns it
+    // into a result map instead.
+    interpreter.define_native("jsonParse", 1, |_, arguments| {
+        JsonParser::new(string_arg(&arguments[0], "jsonParse")?).parse()
+    });
+    interpreter.define_native("jsonStringify", 2, |_, arguments| {
+        let indent = match &arguments[1] {
+            CallableRet::Value(LiteralValue::NilLiteral) => 0,
+            CallableRet::Value(LiteralValue::NumberLiteral(n))
+                if n.fract() == 0.0 && (0.0..=16.0).contains(n) =>
+            {
+                *n as usize
+            }
+            _ => {
+                return Err(RuntimeException::InvalidArgument(String::from(
+                    "jsonStringify() indent must be nil or an integer between 0 and 16.",
+                )))
+            }
+        };
+        let mut writer = JsonWriter {
+            out: String::new(),
+            indent,
+            path: Vec::new(),
+        };
+        writer.write(&arguments[0], 0)?;
+        Ok(string(writer.out))
+    });
+}
+
+struct JsonParser {
+    chars: Vec<char>,
+    current: usize,
+    line: usize,
+    column: usize,
+    depth: usize,
+}
+
+impl JsonParser {
+    fn new(text: &str) -> Self {
+        Self {
+            chars: text.chars().collect(),
+            current: 0,
+            line: 1,
+            column: 1,
+            depth: 0,
+        }
+    }
+
+    fn parse(&mut self) -> Result<CallableRet, RuntimeException> {
+        let value = self.value()?;
+        self.skip_whitespace();
+        if self.peek().is_some() {
+            return Err(self.error("unexpected text after the JSON value"));
+        }
+        Ok(value)
+    }
+
+    fn value(&mut self) -> Result<CallableRet, RuntimeException> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => self.nested(Self::object),
+            Some('[') => self.nested(Self::array),
+            Some('"') => Ok(string(self.string()?)),
+            Some('t') => self.keyword("true", bool(true)),
+            Some('f') => self.keyword("false", bool(false)),
+            Some('n') => self.keyword("null", nil()),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number(),
+            Some(_) => Err(self.error("expected a value")),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn nested(
+        &mut self,
+        parse: fn(&mut Self) -> Result<CallableRet, RuntimeException>,
+    ) -> Result<CallableRet, RuntimeException> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error("nesting is too deep"));
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn object(&mut self) -> Result<CallableRet, RuntimeException> {
+        self.advance();
+        let mut entries = BTreeMap::new();
+        self.skip_whitespace();
+        if self.match_char('}') {
+            return Ok(CallableRet::Map(Rc::new(RefCell::new(entries))));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some('"') {
+                return Err(self.error("expected a string key"));
+            }
+            let key = self.string()?;
+            self.skip_whitespace();
+            if !self.match_char(':') {
+                return Err(self.error("expected ':' after key"));
+            }
+            let value = self.value()?;
+            entries.insert(key, value);
+            self.skip_whitespace();
+            if self.match_char('}') {
+                return Ok(CallableRet::Map(Rc::new(RefCell::new(entries))));
+            }
+            if !self.match_char(',') {
+                return Err(self.error("expected ',' or '}' in object"));
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<CallableRet, RuntimeException> {
+        self.advance();
+        let mut elements = Vec::new();
+        self.skip_whitespace();
+        if self.match_char(']') {
+            return Ok(CallableRet::List(Rc::new(RefCell::new(elements))));
+        }
+        loop {
+            elements.push(self.value()?);
+            self.skip_whitespace();
+            if self.match_char(']') {
+                return Ok(CallableRet::List(Rc::new(RefCell::new(elements))));
+            }
+            if !self.match_char(',') {
+                return Err(self.error("expected ',' or ']' in array"));
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, RuntimeException> {
+        self.advance();
+        let mut s = String::new();
+        loop {
+            match self.advance() {
+                Some('"') => return Ok(s),
+                Some('\\') => {
+                    let c = match self.advance() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => self.unicode_escape()?,
+                        _ => return Err(self.error("invalid escape in string")),
+                    };
+                    s.push(c);
+                }
+                Some(c) if (c as u32) < 0x20 => {
+                    return Err(self.error("control character in string"))
+                }
+                Some(c) => s.push(c),
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    // Characters outside the Basic Multilingual Plane arrive as a surrogate
+    // pair of `\u` escapes.
+    fn unicode_escape(&mut self) -> Result<char, RuntimeException> {
+        let high = self.hex4()?;
+        let code = if (0xD800..0xDC00).contains(&high) {
+            if !(self.match_char('\\') && self.match_char('u')) {
+                return Err(self.error("unpaired surrogate in string"));
+            }
+            let low = self.hex4()?;
+            if !(0xDC00..0xE000).contains(&low) {
+                return Err(self.error("unpaired surrogate in string"));
+            }
+            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        } else {
+            high
+        };
+        char::from_u32(code).ok_or_else(|| self.error("unpaired surrogate in string"))
+    }
+
+    fn hex4(&mut self) -> Result<u32, RuntimeException> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = self
+                .advance()
+                .and_then(|c| c.to_digit(16))
+                .ok_or_else(|| self.error("invalid \\u escape"))?;
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+
+    fn number(&mut self) -> Result<CallableRet, RuntimeException> {
+        let start = self.current;
+        self.match_char('-');
+        if !self.match_char('0') && !self.digits() {
+            return Err(self.error("invalid number"));
+        }
+        if self.match_char('.') && !self.digits() {
+            return Err(self.error("expected digits after '.'"));
+        }
+        if matches!(self.peek(), Some('e' | 'E')) {
+            self.advance();
+            if !self.match_char('+') {
+                self.match_char('-');
+            }
+            if !self.digits() {
+                return Err(self.error("expected digits in exponent"));
+            }
+        }
+        let text: String = self.chars[start..self.current].iter().collect();
+        text.parse()
+            .map(number)
+            .map_err(|_| self.error("invalid number"))
+    }
+
+    fn digits(&mut self) -> bool {
+        let start = self.current;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.advance();
+        }
+        self.current > start
+    }
+
+    fn keyword(
+        &mut self,
+        keyword: &str,
+        value: CallableRet,
+    ) -> Result<CallableRet, RuntimeException> {
+        for expected in keyword.chars() {
+            if !self.match_char(expected) {
+                return Err(self.error("expected a value"));
+            }
+        }
+        Ok(value)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t' | '\n' | '\r')) {
+            self.advance();
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.current).copied()
+    }
+
+    fn advance(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.current += 1;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn match_char(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.advance();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn error(&self, msg: &str) -> RuntimeException {
+        RuntimeException::InvalidArgument(format!(
+            "Invalid JSON at line {}, column {}: {}.",
+            self.line, self.column, msg
+        ))
+    }
+}
+
+struct JsonWriter {
+    out: String,
+    indent: usize,
+    // Lists and maps currently being written, to catch cycles.
+    path: Vec<*const ()>,
+}
+
+impl JsonWriter {
+    fn write(&mut self, value: &CallableRet, depth: usize) -> Result<(), RuntimeException> {
+        match value {
+            CallableRet::Value(LiteralValue::NilLiteral) => self.out.push_str("null"),
+            CallableRet::Value(LiteralValue::BoolLiteral(b)) => self.out.push_str(&b.to_string()),
+            CallableRet::Value(LiteralValue::NumberLiteral(n)) if n.is_finite() => {
+                self.out.push_str(&n.to_string())
+            }
+            CallableRet::Value(LiteralValue::NumberLiteral(_)) => {
+                return Err(RuntimeException::InvalidArgument(String::from(
+                    "Can't convert NaN or infinity to JSON.",
+                )))
+            }
+            CallableRet::Value(LiteralValue::StringLiteral(s)) => self.write_string(s),
+            CallableRet::List(list) => {
+                self.enter(Rc::as_ptr(list) as *const (), depth)?;
+                let elements = list.borrow().clone();
+                self.out.push('[');
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 {
+                        self.out.push(',');
+                    }
+                    self.newline(depth + 1);
+                    self.write(element, depth + 1)?;
+                }
+                if !elements.is_empty() {
+                    self.newline(depth);
+                }
+                self.out.push(']');
+                self.path.pop();
+            }
+            CallableRet::Map(map) => {
+                self.enter(Rc::as_ptr(map) as *const (), depth)?;
+                let entries = map.borrow().clone();
+                self.out.push('{');
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        self.out.push(',');
+                    }
+                    self.newline(depth + 1);
+                    self.write_string(key);
+                    self.out.push(':');
+                    if self.indent > 0 {
+                        self.out.push(' ');
+                    }
+                    self.write(value, depth + 1)?;
+                }
+                if !entries.is_empty() {
+                    self.newline(depth);
+                }
+                self.out.push('}');
+                self.path.pop();
+            }
+            value => {
+                return Err(RuntimeException::InvalidArgument(format!(
+                    "Can't convert {value} to JSON."
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    fn enter(&mut self, container: *const (), depth: usize) -> Result<(), RuntimeException> {
+        if depth == MAX_DEPTH {
+            return Err(RuntimeException::InvalidArgument(format!(
+                "Can't convert values nested more than {MAX_DEPTH} deep to JSON."
+            )));
+        }
+        if self.path.contains(&container) {
+            return Err(RuntimeException::InvalidArgument(String::from(
+                "Can't convert a cyclic structure to JSON.",
+            )));
+        }
+        self.path.push(container);
+        Ok(())
+    }
+
+    fn newline(&mut self, depth: usize) {
+        if self.indent > 0 {
+            self.out.push('\n');
+            self.out.push_str(&" ".repeat(self.indent * depth));
+        }
+    }
+
+    fn write_string(&mut self, s: &str) {
+        self.out.push('"');
+        for c in s.chars() {
+            match c {
+                '"' => self.out.push_str("\\\""),
+                '\\' => self.out.push_str("\\\\"),
+                '\n' => self.out.push_str("\\n"),
+                '\r' => self.out.push_str("\\r"),
+                '\t' => self.out.push_str("\\t"),
+                c if (c as u32) < 0x20 => self.out.push_str(&format!("\\u{:04x}", c as u32)),
+                c => self.out.push(c),
+            }
+        }
+        self.out.push('"');
+    }
+}
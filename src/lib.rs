@@ -1,8 +1,24 @@
+pub mod builtins;
 pub mod callable;
+pub mod checker;
+pub mod class;
 pub mod environment;
 pub mod expression;
+pub mod files;
+pub mod generator;
+pub mod input;
 pub mod interpreter;
+pub mod iterator;
+pub mod json;
+pub mod math;
+pub mod native;
 pub mod parser;
+pub mod pattern;
+pub mod random;
 pub mod scanner;
+pub mod scheduler;
 pub mod statement;
+pub mod strings;
+pub mod system;
+pub mod time;
 pub mod token;
@@ -3,26 +3,61 @@ use std::fs;
 use std::io::{self, Write};
 use std::process::exit;
 
-use codecrafters_interpreter::callable::*;
+use codecrafters_interpreter::checker::TypeChecker;
 use codecrafters_interpreter::expression::ast_printer::AstPrinter;
+use codecrafters_interpreter::files;
 use codecrafters_interpreter::interpreter::*;
 use codecrafters_interpreter::parser::*;
+use codecrafters_interpreter::random::Rng;
 use codecrafters_interpreter::scanner::*;
 
 fn main() {
     let args: Vec<String> = env::args().collect();
     if args.len() < 3 {
-        writeln!(io::stderr(), "Usage: {} tokenize <filename>", args[0]).unwrap();
+        eprintln!("Usage: {} tokenize <filename>", args[0]);
         return;
     }
 
     let command = &args[1];
-    let filename = &args[2];
+    let mut fake_clock = false;
+    let mut fs_root = None;
+    let mut seed = None;
+    let mut rest = args[2..].iter();
+    let filename = loop {
+        match rest.next().map(String::as_str) {
+            Some("--fake-clock") => fake_clock = true,
+            Some("--fs-root") => match rest.next().map(|dir| files::canonical_root(dir)) {
+                Some(Some(root)) => fs_root = Some(root),
+                _ => {
+                    eprintln!("--fs-root needs an existing directory");
+                    exit(64);
+                }
+            },
+            Some("--seed") => match rest.next().map(|n| n.parse::<u64>()) {
+                Some(Ok(n)) => seed = Some(n),
+                _ => {
+                    eprintln!("--seed needs a non-negative integer");
+                    exit(64);
+                }
+            },
+            Some(flag) if flag.starts_with("--") => {
+                eprintln!("Unknown flag: {}", flag);
+                exit(64);
+            }
+            Some(filename) => break filename,
+            None => {
+                eprintln!("Usage: {} {} <filename>", args[0], command);
+                exit(64);
+            }
+        }
+    };
+
+    let script_args: Vec<String> = rest.cloned().collect();
 
     match command.as_str() {
         "tokenize" => {
             let file_contents = fs::read_to_string(filename).unwrap_or_else(|_| {
-                writeln!(io::stderr(), "Failed to read file {}", filename).unwrap();
+                eprintln!("Failed to read file {}", filename);
                 String::new()
             });
 
@@ -36,7 +71,7 @@ fn main() {
         }
         "parse" => {
             let file_contents = fs::read_to_string(filename).unwrap_or_else(|_| {
-                writeln!(io::stderr(), "Failed to read file {}", filename).unwrap();
+                eprintln!("Failed to read file {}", filename);
                 String::new()
             });
             let mut scanner = Scanner::new(file_contents.trim_end());
@@ -57,7 +92,7 @@ fn main() {
         }
         "evaluate" => {
             let file_contents = fs::read_to_string(filename).unwrap_or_else(|_| {
-                writeln!(io::stderr(), "Failed to read file {}", filename).unwrap();
+                eprintln!("Failed to read file {}", filename);
                 String::new()
             });
             let mut scanner = Scanner::new(file_contents.trim_end());
@@ -72,13 +107,14 @@ fn main() {
                 Ok(expr) => {
                     let mut evaluator = Interpreter::new();
                     match evaluator.evaluate(&expr) {
-                        Ok(ret) => match ret {
-                            CallableRet::Value(val) => println!("{val}"),
-                            CallableRet::Callable(_) => unimplemented!(),
-                        },
+                        Ok(ret) => println!("{ret}"),
+                        Err(RuntimeException::Exit(code)) => {
+                            let _ = io::stdout().flush();
+                            exit(code);
+                        }
                         Err(e) => {
                             eprintln!("{e}");
-                            exit(70);
+                            exit(e.exit_code().unwrap_or(70));
                         }
                     }
                 }
@@ -87,7 +123,7 @@ fn main() {
         }
         "run" => {
             let file_contents = fs::read_to_string(filename).unwrap_or_else(|_| {
-                writeln!(io::stderr(), "Failed to read file {}", filename).unwrap();
+                eprintln!("Failed to read file {}", filename);
                 String::new()
             });
             let mut scanner = Scanner::new(file_contents.trim_end());
@@ -101,11 +137,23 @@ fn main() {
             match parser.status {
                 ParserStatus::Success => {
                     let mut interpreter = Interpreter::new();
+                    if fake_clock {
+                        interpreter.scheduler.use_fake_clock();
+                    }
+                    interpreter.fs_root = fs_root;
+                    interpreter.args = script_args;
+                    if let Some(seed) = seed {
+                        interpreter.rng = Rng::from_seed(seed);
+                    }
                     match interpreter.interprete(&stmts) {
                         Ok(()) => exit(0),
+                        Err(RuntimeException::Exit(code)) => {
+                            let _ = io::stdout().flush();
+                            exit(code);
+                        }
                         Err(e) => {
                             eprintln!("{e}");
-                            exit(70);
+                            exit(e.exit_code().unwrap_or(70));
                         }
                     }
                 }
@@ -114,9 +162,37 @@ fn main() {
                 }
             }
         }
+        "check" => {
+            let file_contents = fs::read_to_string(filename).unwrap_or_else(|_| {
+                eprintln!("Failed to read file {}", filename);
+                String::new()
+            });
+            let mut scanner = Scanner::new(file_contents.trim_end());
+            scanner.scan_tokens();
+            match &scanner.status {
+                ScannerStatus::ScanSuccess => {}
+                _ => exit(65),
+            }
+            let mut parser = Parser::new(scanner.tokens);
+            let stmts = parser.parse();
+            match parser.status {
+                ParserStatus::Success => {
+                    let mut checker = TypeChecker::new();
+                    checker.check(&stmts);
+                    for e in &checker.errors {
+                        eprintln!("{e}");
+                    }
+                    if !checker.errors.is_empty() {
+                        exit(65);
+                    }
+                }
+                ParserStatus::Panic => {
+                    exit(65);
+                }
+            }
+        }
         _ => {
-            writeln!(io::stderr(), "Unknown command: {}", command).unwrap();
-            return;
+            eprintln!("Unknown command: {}", command);
         }
     }
 }
@@ -0,0 +1,54 @@
+use std::f64::consts::{E, PI};
+
+use crate::interpreter::*;
+use crate::native::{number, number_arg};
+
+type Unary = fn(f64) -> f64;
+type Binary = fn(f64, f64) -> f64;
+
+const UNARY: [(&str, Unary); 10] = [
+    ("sqrt", f64::sqrt),
+    ("abs", f64::abs),
+    ("floor", f64::floor),
+    ("ceil", f64::ceil),
+    ("round", f64::round),
+    ("sin", f64::sin),
+    ("cos", f64::cos),
+    ("tan", f64::tan),
+    ("log", f64::ln),
+    ("exp", f64::exp),
+];
+
+const BINARY: [(&str, Binary); 5] = [
+    ("pow", f64::powf),
+    ("min", f64::min),
+    ("max", f64::max),
+    ("atan2", f64::atan2),
+    ("hypot", f64::hypot),
+];
+
+pub fn define(interpreter: &mut Interpreter) {
+    for (name, function) in UNARY {
+        interpreter.define_native(name, 1, move |_, arguments| {
+            Ok(number(function(number_arg(&arguments[0], name)?)))
+        });
+    }
+    for (name, function) in BINARY {
+        interpreter.define_native(name, 2, move |_, arguments| {
+            let a = number_arg(&arguments[0], name)?;
+            let b = number_arg(&arguments[1], name)?;
+            Ok(number(function(a, b)))
+        });
+    }
+    for (name, value) in [
+        ("PI", PI),
+        ("E", E),
+        ("INF", f64::INFINITY),
+        ("NAN", f64::NAN),
+    ] {
+        interpreter
+            .globals
+            .borrow_mut()
+            .define(name, Some(number(value)));
+    }
+}
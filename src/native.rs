@@ -0,0 +1,153 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use crate::callable::*;
+use crate::interpreter::*;
+use crate::scheduler::*;
+use crate::token::LiteralValue;
+
+pub type NativeFn =
+    dyn Fn(&mut Interpreter, &[CallableRet]) -> Result<CallableRet, RuntimeException>;
+
+// A built-in function implemented in Rust. The interpreter checks the arity
+// before calling, so `function` can index into its arguments freely.
+#[derive(Clone)]
+pub struct NativeFunction {
+    pub name: String,
+    pub arity: usize,
+    pub function: Rc<NativeFn>,
+}
+
+pub fn define(interpreter: &mut Interpreter) {
+    interpreter.define_native("clock", 0, |interpreter, _| {
+        Ok(CallableRet::Value(LiteralValue::NumberLiteral(
+            interpreter.scheduler.now(),
+        )))
+    });
+    interpreter.define_native("range", 2, |_, arguments| {
+        match (&arguments[0], &arguments[1]) {
+            (
+                CallableRet::Value(LiteralValue::NumberLiteral(start)),
+                CallableRet::Value(LiteralValue::NumberLiteral(end)),
+            ) => Ok(CallableRet::Range(*start, *end)),
+            _ => Err(RuntimeException::InvalidArgument(String::from(
+                "Range bounds must be numbers.",
+            ))),
+        }
+    });
+    interpreter.define_native("spawn", 1, |interpreter, arguments| {
+        let task = match &arguments[0] {
+            CallableRet::Generator(generator) => Task::Running(generator.clone()),
+            CallableRet::Callable(callable) if callable.arity() == 0 => {
+                Task::Start(callable.clone())
+            }
+            _ => {
+                return Err(RuntimeException::InvalidArgument(String::from(
+                    "Can only spawn generators and functions without parameters.",
+                )))
+            }
+        };
+        interpreter.scheduler.spawn(task);
+        Ok(CallableRet::Value(LiteralValue::NilLiteral))
+    });
+    // The result only does something when a task yields it, as in
+    // `yield sleep(ms);`.
+    interpreter.define_native("sleep", 1, |interpreter, arguments| match &arguments[0] {
+        _ if !interpreter.scheduler.in_task() => Err(RuntimeException::InvalidArgument(
+            String::from("sleep() can only be awaited with yield inside a spawned task."),
+        )),
+        CallableRet::Value(LiteralValue::NumberLiteral(ms)) => {
+            Ok(CallableRet::Await(Await::Sleep(*ms)))
+        }
+        _ => Err(RuntimeException::InvalidArgument(String::from(
+            "Sleep duration must be a number.",
+        ))),
+    });
+    // Calls a function and turns any runtime error it raises into a value,
+    // so scripts can recover from bad input or failed I/O. `exit()` still
+    // ends the script.
+    interpreter.define_native("try", 2, |interpreter, arguments| {
+        let CallableRet::Callable(mut function) = arguments[0].clone() else {
+            return Err(RuntimeException::InvalidArgument(String::from(
+                "First argument to try() must be a function.",
+            )));
+        };
+        let CallableRet::List(list) = &arguments[1] else {
+            return Err(RuntimeException::InvalidArgument(String::from(
+                "Second argument to try() must be a list of arguments.",
+            )));
+        };
+        let arguments = list.borrow().clone();
+        if arguments.len() != function.arity() {
+            return Err(RuntimeException::UnmatchedArity(
+                function.arity(),
+                arguments.len(),
+            ));
+        }
+        let entries = match function.call(interpreter, &arguments) {
+            Ok(value) => BTreeMap::from([
+                (String::from("ok"), bool(true)),
+                (String::from("value"), value),
+            ]),
+            Err(e) if e.exit_code().is_some() => return Err(e),
+            Err(e) => BTreeMap::from([
+                (String::from("ok"), bool(false)),
+                (String::from("error"), string(e.message())),
+                (
+                    String::from("line"),
+                    e.line().map_or_else(nil, |line| number(line as f64)),
+                ),
+            ]),
+        };
+        Ok(CallableRet::Map(Rc::new(RefCell::new(entries))))
+    });
+    interpreter.define_native("channel", 0, |_, _| {
+        Ok(CallableRet::Channel(Rc::new(RefCell::new(
+            Channel::default(),
+        ))))
+    });
+}
+
+// Argument checks and value constructors shared by the native modules.
+
+pub fn string_arg<'a>(
+    argument: &'a CallableRet,
+    function: &str,
+) -> Result<&'a str, RuntimeException> {
+    match argument {
+        CallableRet::Value(LiteralValue::StringLiteral(s)) => Ok(s),
+        _ => Err(RuntimeException::InvalidArgument(format!(
+            "Argument to {function}() must be a string."
+        ))),
+    }
+}
+
+pub fn number_arg(argument: &CallableRet, function: &str) -> Result<f64, RuntimeException> {
+    match argument {
+        CallableRet::Value(LiteralValue::NumberLiteral(n)) => Ok(*n),
+        _ => Err(RuntimeException::InvalidArgument(format!(
+            "Arguments to {function}() must be numbers."
+        ))),
+    }
+}
+
+pub fn number(n: f64) -> CallableRet {
+    CallableRet::Value(LiteralValue::NumberLiteral(n))
+}
+
+pub fn string(s: String) -> CallableRet {
+    CallableRet::Value(LiteralValue::StringLiteral(s))
+}
+
+pub fn bool(b: bool) -> CallableRet {
+    CallableRet::Value(LiteralValue::BoolLiteral(b))
+}
+
+pub fn nil() -> CallableRet {
+    CallableRet::Value(LiteralValue::NilLiteral)
+}
+
+pub fn list(elements: Vec<CallableRet>) -> CallableRet {
+    CallableRet::List(Rc::new(RefCell::new(elements)))
+}
@@ -6,6 +6,10 @@ use std::fmt;
 pub struct Parser {
     tokens: Vec<Token>,
     current: usize,
+    in_generator: bool,
+    in_initializer: bool,
+    in_function: bool,
+    class_kind: ClassKind,
     pub status: ParserStatus,
 }
 
@@ -14,6 +18,20 @@ pub struct ParserError {
     msg: String,
 }
 
+#[derive(Clone, Copy, PartialEq)]
+enum ClassKind {
+    None,
+    Class,
+    Subclass,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum FunctionKind {
+    Function,
+    Method,
+    Getter,
+}
+
 pub enum ParserStatus {
     Success,
     Panic,
@@ -54,6 +72,10 @@ impl Parser {
         Self {
             tokens,
             current: 0,
+            in_generator: false,
+            in_initializer: false,
+            in_function: false,
+            class_kind: ClassKind::None,
             status: ParserStatus::Success,
         }
     }
@@ -74,10 +96,18 @@ impl Parser {
     }
 
     fn declaration(&mut self) -> Option<Stmt> {
-        match if self.match_then_advance(vec![TokenType::FUN]) {
-            self.function()
+        match if self.match_then_advance(vec![TokenType::CLASS]) {
+            self.class_declaration()
+        } else if self.match_then_advance(vec![TokenType::TRAIT]) {
+            self.trait_declaration()
+        } else if self.match_then_advance(vec![TokenType::FUN]) {
+            self.function(FunctionKind::Function)
+        } else if self.check(TokenType::AT) {
+            self.decorated_function()
         } else if self.match_then_advance(vec![TokenType::VAR]) {
             self.var_declaration()
+        } else if self.match_then_advance(vec![TokenType::ENUM]) {
+            self.enum_declaration()
         } else {
             self.statement()
         } {
@@ -91,51 +121,358 @@ impl Parser {
         }
     }
 
-    fn function(&mut self) -> Result<Stmt, ParserError> {
+    fn enum_declaration(&mut self) -> Result<Stmt, ParserError> {
         let name = self
-            .consume(TokenType::IDENTIFIER, "expect function name.")?
+            .consume(TokenType::IDENTIFIER, "expect enum name.")?
             .clone();
-        self.consume(TokenType::LEFT_PAREN, "expect '(' after function name.")?;
-        let mut params = Vec::new();
-        if !self.check(TokenType::RIGHT_PAREN) {
+        self.consume(TokenType::LEFT_BRACE, "expect '{' before enum body.")?;
+        let mut variants: Vec<Token> = Vec::new();
+        while !self.check(TokenType::RIGHT_BRACE) {
+            let variant = self
+                .consume(TokenType::IDENTIFIER, "expect variant name.")?
+                .clone();
+            if variant.lexeme == "values" {
+                return Err(ParserError::new(
+                    variant,
+                    "'values' can't be a variant name.",
+                ));
+            }
+            if variants.iter().any(|other| other.lexeme == variant.lexeme) {
+                return Err(ParserError::new(variant, "Duplicate variant in enum."));
+            }
+            variants.push(variant);
+            if !self.match_then_advance(vec![TokenType::COMMA]) {
+                break;
+            }
+        }
+        self.consume(TokenType::RIGHT_BRACE, "expect '}' after enum body.")?;
+        Ok(Stmt::EnumStmt(EnumStmtInner { name, variants }))
+    }
+
+    fn class_declaration(&mut self) -> Result<Stmt, ParserError> {
+        let name = self
+            .consume(TokenType::IDENTIFIER, "expect class name.")?
+            .clone();
+        let superclass = if self.match_then_advance(vec![TokenType::LESS]) {
+            let superclass = self
+                .consume(TokenType::IDENTIFIER, "expect superclass name.")?
+                .clone();
+            if superclass.lexeme == name.lexeme {
+                return Err(ParserError::new(
+                    superclass,
+                    "A class can't inherit from itself.",
+                ));
+            }
+            Some(Var::new(superclass))
+        } else {
+            None
+        };
+        let mut traits = Vec::new();
+        if self.match_then_advance(vec![TokenType::WITH]) {
             loop {
-                if params.len() >= 255 {
+                let name = self.consume(TokenType::IDENTIFIER, "expect trait name.")?;
+                traits.push(Var::new(name.clone()));
+                if !self.match_then_advance(vec![TokenType::COMMA]) {
+                    break;
+                }
+            }
+        }
+        self.consume(TokenType::LEFT_BRACE, "expect '{' before class body.")?;
+        let kind = if superclass.is_some() {
+            ClassKind::Subclass
+        } else {
+            ClassKind::Class
+        };
+        let mut class = ClassStmtInner {
+            name,
+            superclass,
+            traits,
+            methods: Vec::new(),
+            getters: Vec::new(),
+            setters: Vec::new(),
+            class_methods: Vec::new(),
+            private: Vec::new(),
+        };
+        let enclosing_class = std::mem::replace(&mut self.class_kind, kind);
+        let body = self.class_body(&mut class);
+        self.class_kind = enclosing_class;
+        body?;
+        Ok(Stmt::ClassStmt(class))
+    }
+
+    // A method signature without a body, `name(params);`, lists a method that
+    // classes including the trait must provide.
+    fn trait_declaration(&mut self) -> Result<Stmt, ParserError> {
+        let name = self
+            .consume(TokenType::IDENTIFIER, "expect trait name.")?
+            .clone();
+        self.consume(TokenType::LEFT_BRACE, "expect '{' before trait body.")?;
+        let enclosing_class = std::mem::replace(&mut self.class_kind, ClassKind::Class);
+        let body = self.trait_body();
+        self.class_kind = enclosing_class;
+        let (methods, required) = body?;
+        Ok(Stmt::TraitStmt(TraitStmtInner {
+            name,
+            methods,
+            required,
+        }))
+    }
+
+    fn trait_body(&mut self) -> Result<(Vec<FunctionStmtInner>, Vec<Token>), ParserError> {
+        let mut methods = Vec::new();
+        let mut required = Vec::new();
+        while !self.check(TokenType::RIGHT_BRACE) && !self.end() {
+            let decorators = self.decorators()?;
+            let start = self.current;
+            if decorators.is_empty()
+                && self.check(TokenType::IDENTIFIER)
+                && self.check_ahead(1, TokenType::LEFT_PAREN)
+            {
+                let name = self.advance().clone();
+                while !self.check(TokenType::RIGHT_PAREN) && !self.end() {
+                    self.advance();
+                }
+                self.consume(TokenType::RIGHT_PAREN, "expect ')' after parameters")?;
+                self.type_annotation()?;
+                if self.match_then_advance(vec![TokenType::SEMICOLON]) {
+                    required.push(name);
+                    continue;
+                }
+                self.current = start;
+            }
+            let method = self.method(FunctionKind::Method)?;
+            methods.push(self.decorated(method, decorators)?);
+        }
+        self.consume(TokenType::RIGHT_BRACE, "expect '}' after trait body.")?;
+        Ok((methods, required))
+    }
+
+    // `class name() {}` declares a static method, `set name(v) {}` a setter
+    // and `name {}` (no parameter list) a getter. Any of them can be marked
+    // `private`, which members named `#name` are implicitly.
+    fn class_body(&mut self, class: &mut ClassStmtInner) -> Result<(), ParserError> {
+        while !self.check(TokenType::RIGHT_BRACE) && !self.end() {
+            let decorators = self.decorators()?;
+            let private = self.check(TokenType::IDENTIFIER)
+                && self.peek().lexeme == "private"
+                && !self.check_ahead(1, TokenType::LEFT_PAREN)
+                && !self.check_ahead(1, TokenType::LEFT_BRACE);
+            if private {
+                self.advance();
+            }
+            let member = if self.match_then_advance(vec![TokenType::CLASS]) {
+                let method = self.method(FunctionKind::Function)?;
+                class
+                    .class_methods
+                    .push(self.decorated(method, decorators)?);
+                class.class_methods.last()
+            } else {
+                let is_setter = self.check(TokenType::IDENTIFIER)
+                    && self.peek().lexeme == "set"
+                    && self.check_ahead(1, TokenType::IDENTIFIER);
+                let is_getter =
+                    self.check(TokenType::IDENTIFIER) && self.check_ahead(1, TokenType::LEFT_BRACE);
+                if !decorators.is_empty() && (is_setter || is_getter) {
                     return Err(ParserError::new(
                         self.peek().clone(),
-                        "Can't have more than 255 parameters.",
+                        "Getters and setters can't be decorated.",
                     ));
+                }
+                if is_setter {
+                    self.advance();
+                    let setter = self.method(FunctionKind::Method)?;
+                    if setter.params.len() != 1 {
+                        return Err(ParserError::new(
+                            setter.name,
+                            "A setter must take exactly one parameter.",
+                        ));
+                    }
+                    class.setters.push(setter);
+                    class.setters.last()
+                } else if is_getter {
+                    class.getters.push(self.method(FunctionKind::Getter)?);
+                    class.getters.last()
                 } else {
-                    params.push(
-                        self.consume(TokenType::IDENTIFIER, "expect parameter name.")?
-                            .clone(),
-                    );
+                    let method = self.method(FunctionKind::Method)?;
+                    class.methods.push(self.decorated(method, decorators)?);
+                    class.methods.last()
                 }
-                if !self.match_then_advance(vec![TokenType::COMMA]) {
-                    break;
+            };
+            if private {
+                let name = member.unwrap().name.clone();
+                class.private.push(name);
+            }
+        }
+        self.consume(TokenType::RIGHT_BRACE, "expect '}' after class body.")?;
+        Ok(())
+    }
+
+    fn decorated_function(&mut self) -> Result<Stmt, ParserError> {
+        let decorators = self.decorators()?;
+        self.consume(TokenType::FUN, "expect function after decorators.")?;
+        let mut function = self.method(FunctionKind::Function)?;
+        function.decorators = decorators;
+        Ok(Stmt::FunctionStmt(function))
+    }
+
+    // Instantiation calls `init` directly, so it can't be swapped out.
+    fn decorated(
+        &self,
+        mut method: FunctionStmtInner,
+        decorators: Vec<Expr>,
+    ) -> Result<FunctionStmtInner, ParserError> {
+        if !decorators.is_empty() && method.name.lexeme == "init" {
+            return Err(ParserError::new(
+                method.name,
+                "An initializer can't be decorated.",
+            ));
+        }
+        method.decorators = decorators;
+        Ok(method)
+    }
+
+    fn decorators(&mut self) -> Result<Vec<Expr>, ParserError> {
+        let mut decorators = Vec::new();
+        while self.match_then_advance(vec![TokenType::AT]) {
+            decorators.push(*self.call()?);
+        }
+        Ok(decorators)
+    }
+
+    fn method(&mut self, kind: FunctionKind) -> Result<FunctionStmtInner, ParserError> {
+        match self.function(kind)? {
+            Stmt::FunctionStmt(method) => Ok(method),
+            _ => unreachable!(),
+        }
+    }
+
+    fn function(&mut self, kind: FunctionKind) -> Result<Stmt, ParserError> {
+        let is_generator = self.match_then_advance(vec![TokenType::STAR]);
+        let name = self
+            .consume(TokenType::IDENTIFIER, "expect function name.")?
+            .clone();
+        let mut params = Vec::new();
+        let mut param_types = Vec::new();
+        if kind != FunctionKind::Getter {
+            self.consume(TokenType::LEFT_PAREN, "expect '(' after function name.")?;
+            if !self.check(TokenType::RIGHT_PAREN) {
+                loop {
+                    if params.len() >= 255 {
+                        return Err(ParserError::new(
+                            self.peek().clone(),
+                            "Can't have more than 255 parameters.",
+                        ));
+                    } else {
+                        params.push(
+                            self.consume(TokenType::IDENTIFIER, "expect parameter name.")?
+                                .clone(),
+                        );
+                        param_types.push(self.type_annotation()?);
+                    }
+                    if !self.match_then_advance(vec![TokenType::COMMA]) {
+                        break;
+                    }
                 }
             }
+            self.consume(TokenType::RIGHT_PAREN, "expect ')' after parameters")?;
         }
-        self.consume(TokenType::RIGHT_PAREN, "expect ')' after parameters")?;
+        let return_type = self.type_annotation()?;
         self.consume(TokenType::LEFT_BRACE, "expect '{' before function body.")?;
-        let body = self.block_statement()?;
+        let is_initializer = kind == FunctionKind::Method && name.lexeme == "init";
+        let enclosing_generator = std::mem::replace(&mut self.in_generator, is_generator);
+        let enclosing_initializer = std::mem::replace(&mut self.in_initializer, is_initializer);
+        let enclosing_function = std::mem::replace(&mut self.in_function, true);
+        let body = self.block_statement();
+        self.in_generator = enclosing_generator;
+        self.in_initializer = enclosing_initializer;
+        self.in_function = enclosing_function;
         Ok(Stmt::FunctionStmt(FunctionStmtInner::new(
             name,
             params,
-            Box::new(body),
+            param_types,
+            return_type,
+            Box::new(body?),
+            is_generator,
         )))
     }
 
+    fn type_annotation(&mut self) -> Result<Option<Token>, ParserError> {
+        if self.match_then_advance(vec![TokenType::COLON]) {
+            Ok(Some(
+                self.consume(TokenType::IDENTIFIER, "expect type name after ':'.")?
+                    .clone(),
+            ))
+        } else {
+            Ok(None)
+        }
+    }
+
     fn var_declaration(&mut self) -> Result<Stmt, ParserError> {
-        let name = self
-            .consume(TokenType::IDENTIFIER, "Expect variable name")?
-            .clone();
+        let pattern = self.pattern()?;
+        let annotation = match pattern {
+            Pattern::Name(_) => self.type_annotation()?,
+            _ => None,
+        };
         let init = if self.match_then_advance(vec![TokenType::EQUAL]) {
+            if self.match_then_advance(vec![TokenType::YIELD]) {
+                return match pattern {
+                    Pattern::Name(name) => self.yield_statement(Some(YieldTarget::Declare(name))),
+                    _ => Err(ParserError::new(
+                        self.previous().clone(),
+                        "Can only yield into a single variable.",
+                    )),
+                };
+            }
             Some(self.expression()?)
         } else {
             None
         };
+        if init.is_none() && !matches!(pattern, Pattern::Name(_)) {
+            return Err(ParserError::new(
+                self.peek().clone(),
+                "Expect '=' after destructuring pattern.",
+            ));
+        }
         self.consume(TokenType::SEMICOLON, "Expect ; after variable declaration")?;
-        Ok(Stmt::VarStmt(VarStmtInner(name, init)))
+        Ok(Stmt::VarStmt(VarStmtInner(pattern, init, annotation)))
+    }
+
+    fn pattern(&mut self) -> Result<Pattern, ParserError> {
+        if self.match_then_advance(vec![TokenType::LEFT_BRACKET]) {
+            let bracket = self.previous().clone();
+            let mut elements = Vec::new();
+            if !self.check(TokenType::RIGHT_BRACKET) {
+                loop {
+                    elements.push(self.pattern()?);
+                    if !self.match_then_advance(vec![TokenType::COMMA]) {
+                        break;
+                    }
+                }
+            }
+            self.consume(TokenType::RIGHT_BRACKET, "expect ']' after list pattern.")?;
+            Ok(Pattern::List(bracket, elements))
+        } else if self.match_then_advance(vec![TokenType::LEFT_BRACE]) {
+            let brace = self.previous().clone();
+            let mut names = Vec::new();
+            if !self.check(TokenType::RIGHT_BRACE) {
+                loop {
+                    names.push(
+                        self.consume(TokenType::IDENTIFIER, "Expect variable name")?
+                            .clone(),
+                    );
+                    if !self.match_then_advance(vec![TokenType::COMMA]) {
+                        break;
+                    }
+                }
+            }
+            self.consume(TokenType::RIGHT_BRACE, "expect '}' after map pattern.")?;
+            Ok(Pattern::Map(brace, names))
+        } else {
+            let name = self
+                .consume(TokenType::IDENTIFIER, "Expect variable name")?
+                .clone();
+            Ok(Pattern::Name(name))
+        }
     }
 
     fn statement(&mut self) -> Result<Stmt, ParserError> {
@@ -143,12 +480,26 @@ impl Parser {
             self.for_statement()
         } else if self.match_then_advance(vec![TokenType::IF]) {
             self.if_statement()
+        } else if self.match_then_advance(vec![TokenType::ASSERT]) {
+            self.assert_statement()
+        } else if self.match_then_advance(vec![TokenType::DEFER]) {
+            self.defer_statement()
         } else if self.match_then_advance(vec![TokenType::PRINT]) {
             self.print_statement()
         } else if self.match_then_advance(vec![TokenType::RETURN]) {
             self.return_statement()
         } else if self.match_then_advance(vec![TokenType::WHILE]) {
             self.while_statement()
+        } else if self.match_then_advance(vec![TokenType::YIELD]) {
+            self.yield_statement(None)
+        } else if self.check(TokenType::IDENTIFIER)
+            && self.check_ahead(1, TokenType::EQUAL)
+            && self.check_ahead(2, TokenType::YIELD)
+        {
+            let name = self.advance().clone();
+            self.advance();
+            self.advance();
+            self.yield_statement(Some(YieldTarget::Assign(name)))
         } else if self.match_then_advance(vec![TokenType::LEFT_BRACE]) {
             self.block_statement()
         } else {
@@ -158,6 +509,12 @@ impl Parser {
 
     fn for_statement(&mut self) -> Result<Stmt, ParserError> {
         self.consume(TokenType::LEFT_PAREN, "expect '(' after 'for'.")?;
+        if self.check(TokenType::VAR)
+            && self.check_ahead(1, TokenType::IDENTIFIER)
+            && self.check_ahead(2, TokenType::IN)
+        {
+            return self.for_in_statement();
+        }
         let initializer = if self.match_then_advance(vec![TokenType::SEMICOLON]) {
             None
         } else if self.match_then_advance(vec![TokenType::VAR]) {
@@ -198,6 +555,21 @@ impl Parser {
         Ok(body)
     }
 
+    fn for_in_statement(&mut self) -> Result<Stmt, ParserError> {
+        self.advance();
+        let name = self.advance().clone();
+        let keyword = self.advance().clone();
+        let iterable = self.expression()?;
+        self.consume(TokenType::RIGHT_PAREN, "expect ')' after for-in clause.")?;
+        let body = self.statement()?;
+        Ok(Stmt::ForInStmt(ForInStmtInner::new(
+            name,
+            keyword,
+            iterable,
+            Box::new(body),
+        )))
+    }
+
     fn if_statement(&mut self) -> Result<Stmt, ParserError> {
         self.consume(TokenType::LEFT_PAREN, "expect '(' after 'if'.")?;
         let condition = self.expression()?;
@@ -222,9 +594,47 @@ impl Parser {
         Ok(Stmt::PrintStmt(PrintStmtInner(expr)))
     }
 
+    fn assert_statement(&mut self) -> Result<Stmt, ParserError> {
+        let keyword = self.previous().clone();
+        let start = self.current;
+        let condition = self.expression()?;
+        let source = self.source_text(start);
+        let message = if self.match_then_advance(vec![TokenType::COMMA]) {
+            Some(self.expression()?)
+        } else {
+            None
+        };
+        self.consume(TokenType::SEMICOLON, "expect ';' after assertion.")?;
+        Ok(Stmt::AssertStmt(AssertStmtInner::new(
+            keyword, condition, message, source,
+        )))
+    }
+
+    fn defer_statement(&mut self) -> Result<Stmt, ParserError> {
+        let keyword = self.previous().clone();
+        let expr = self.expression()?;
+        self.consume(
+            TokenType::SEMICOLON,
+            "expect ';' after deferred expression.",
+        )?;
+        Ok(Stmt::DeferStmt(DeferStmtInner { keyword, expr }))
+    }
+
     fn return_statement(&mut self) -> Result<Stmt, ParserError> {
         let keyword = self.previous().clone();
+        if !self.in_function {
+            return Err(ParserError::new(
+                keyword,
+                "Can't return from top-level code.",
+            ));
+        }
         let value = if !self.check(TokenType::SEMICOLON) {
+            if self.in_initializer {
+                return Err(ParserError::new(
+                    keyword,
+                    "Can't return a value from an initializer.",
+                ));
+            }
             Some(self.expression()?)
         } else {
             None
@@ -233,6 +643,23 @@ impl Parser {
         Ok(Stmt::ReturnStmt(ReturnStmtInner::new(keyword, value)))
     }
 
+    fn yield_statement(&mut self, target: Option<YieldTarget>) -> Result<Stmt, ParserError> {
+        let keyword = self.previous().clone();
+        if !self.in_generator {
+            return Err(ParserError::new(
+                keyword,
+                "Can't yield outside of a generator function.",
+            ));
+        }
+        let value = if !self.check(TokenType::SEMICOLON) {
+            Some(self.expression()?)
+        } else {
+            None
+        };
+        self.consume(TokenType::SEMICOLON, "expect ';' after yield value.")?;
+        Ok(Stmt::YieldStmt(YieldStmtInner::new(keyword, value, target)))
+    }
+
     fn while_statement(&mut self) -> Result<Stmt, ParserError> {
         self.consume(TokenType::LEFT_PAREN, "expect '(' after 'while'.")?;
         let condition = self.expression()?;
@@ -270,11 +697,25 @@ impl Parser {
         let expr = self.or()?;
         if self.match_then_advance(vec![TokenType::EQUAL]) {
             let value = self.assignment()?;
-            match expr.as_ref() {
-                Expr::VarExpr(var) => Ok(Box::new(Expr::AssignmentExpr(Assignment::new(
+            match (expr.as_ref(), Self::assignment_pattern(&expr)) {
+                (Expr::VarExpr(var), _) => Ok(Box::new(Expr::AssignmentExpr(Assignment::new(
                     var.name.clone(),
                     value,
                 )))),
+                (Expr::GetExpr(get), _) => Ok(Box::new(Expr::SetExpr(Set::new(
+                    get.object.clone(),
+                    get.name.clone(),
+                    value,
+                )))),
+                (Expr::IndexExpr(index), _) => Ok(Box::new(Expr::IndexSetExpr(IndexSet::new(
+                    index.object.clone(),
+                    index.bracket.clone(),
+                    index.index.clone(),
+                    value,
+                )))),
+                (Expr::ListExpr(_), Some(pattern)) => Ok(Box::new(Expr::DestructureExpr(
+                    Destructure::new(pattern, value),
+                ))),
                 _ => {
                     let e = ParserError::new(self.previous().clone(), "Invalid assignment target");
                     println!("{e}");
@@ -286,6 +727,20 @@ impl Parser {
         }
     }
 
+    fn assignment_pattern(expr: &Expr) -> Option<Pattern> {
+        match expr {
+            Expr::VarExpr(var) => Some(Pattern::Name(var.name.clone())),
+            Expr::ListExpr(list) => {
+                let mut elements = Vec::new();
+                for element in &list.elements {
+                    elements.push(Self::assignment_pattern(element)?);
+                }
+                Some(Pattern::List(list.bracket.clone(), elements))
+            }
+            _ => None,
+        }
+    }
+
     pub fn or(&mut self) -> Result<Box<Expr>, ParserError> {
         let mut left = self.and()?;
         while self.match_then_advance(vec![TokenType::OR]) {
@@ -366,6 +821,22 @@ impl Parser {
         loop {
             if self.match_then_advance(vec![TokenType::LEFT_PAREN]) {
                 expr = self.finish_call(expr)?;
+            } else if self.match_then_advance(vec![TokenType::DOT]) {
+                let name = self
+                    .consume(TokenType::IDENTIFIER, "expect property name after '.'.")?
+                    .clone();
+                if name.lexeme.starts_with('#') && self.class_kind == ClassKind::None {
+                    return Err(ParserError::new(
+                        name,
+                        "Can't use a private name outside of a class.",
+                    ));
+                }
+                expr = Box::new(Expr::GetExpr(Get::new(expr, name)));
+            } else if self.match_then_advance(vec![TokenType::LEFT_BRACKET]) {
+                let bracket = self.previous().clone();
+                let index = self.expression()?;
+                self.consume(TokenType::RIGHT_BRACKET, "expect ']' after index.")?;
+                expr = Box::new(Expr::IndexExpr(Index::new(expr, bracket, index)));
             } else {
                 break;
             }
@@ -429,8 +900,78 @@ impl Parser {
                 }
             }
         }
+        if self.match_then_advance(vec![TokenType::THIS]) {
+            let keyword = self.previous().clone();
+            if self.class_kind == ClassKind::None {
+                return Err(ParserError::new(
+                    keyword,
+                    "Can't use 'this' outside of a class.",
+                ));
+            }
+            return Ok(Box::new(Expr::ThisExpr(This::new(keyword))));
+        }
+        if self.match_then_advance(vec![TokenType::SUPER]) {
+            let keyword = self.previous().clone();
+            match self.class_kind {
+                ClassKind::None => {
+                    return Err(ParserError::new(
+                        keyword,
+                        "Can't use 'super' outside of a class.",
+                    ))
+                }
+                ClassKind::Class => {
+                    return Err(ParserError::new(
+                        keyword,
+                        "Can't use 'super' in a class with no superclass.",
+                    ))
+                }
+                ClassKind::Subclass => {}
+            }
+            self.consume(TokenType::DOT, "expect '.' after 'super'.")?;
+            let method = self
+                .consume(TokenType::IDENTIFIER, "expect superclass method name.")?
+                .clone();
+            return Ok(Box::new(Expr::SuperExpr(Super::new(keyword, method))));
+        }
         if self.match_then_advance(vec![TokenType::IDENTIFIER]) {
-            return Ok(Box::new(Expr::VarExpr(Var::new(self.previous().clone()))));
+            let name = self.previous().clone();
+            if name.lexeme.starts_with('#') {
+                return Err(ParserError::new(
+                    name,
+                    "Private names can only be used as properties.",
+                ));
+            }
+            return Ok(Box::new(Expr::VarExpr(Var::new(name))));
+        }
+        if self.match_then_advance(vec![TokenType::LEFT_BRACKET]) {
+            let bracket = self.previous().clone();
+            let mut elements = Vec::new();
+            if !self.check(TokenType::RIGHT_BRACKET) {
+                loop {
+                    elements.push(self.expression()?);
+                    if !self.match_then_advance(vec![TokenType::COMMA]) {
+                        break;
+                    }
+                }
+            }
+            self.consume(TokenType::RIGHT_BRACKET, "expect ']' after list elements.")?;
+            return Ok(Box::new(Expr::ListExpr(List::new(bracket, elements))));
+        }
+        if self.match_then_advance(vec![TokenType::LEFT_BRACE]) {
+            let brace = self.previous().clone();
+            let mut entries = Vec::new();
+            if !self.check(TokenType::RIGHT_BRACE) {
+                loop {
+                    let key = self.expression()?;
+                    self.consume(TokenType::COLON, "expect ':' after map key.")?;
+                    entries.push((key, self.expression()?));
+                    if !self.match_then_advance(vec![TokenType::COMMA]) {
+                        break;
+                    }
+                }
+            }
+            self.consume(TokenType::RIGHT_BRACE, "expect '}' after map entries.")?;
+            return Ok(Box::new(Expr::MapExpr(Map::new(brace, entries))));
         }
         Err(ParserError::new(self.peek().clone(), "expect expression."))
     }
@@ -444,12 +985,18 @@ impl Parser {
             match self.peek().ttype {
                 TokenType::CLASS
                 | TokenType::FUN
+                | TokenType::AT
+                | TokenType::ENUM
+                | TokenType::TRAIT
                 | TokenType::VAR
                 | TokenType::FOR
                 | TokenType::IF
                 | TokenType::WHILE
                 | TokenType::PRINT
-                | TokenType::RETURN => return,
+                | TokenType::ASSERT
+                | TokenType::DEFER
+                | TokenType::RETURN
+                | TokenType::YIELD => return,
                 _ => {
                     self.advance();
                 }
@@ -457,6 +1004,21 @@ impl Parser {
         }
     }
 
+    // Rebuilds the source of the tokens consumed since `start`; whitespace and
+    // comments between tokens collapse to a single space.
+    fn source_text(&self, start: usize) -> String {
+        let mut text = String::new();
+        let mut end = None;
+        for token in &self.tokens[start..self.current] {
+            if end.is_some_and(|end| token.offset > end) {
+                text.push(' ');
+            }
+            text.push_str(&token.lexeme);
+            end = Some(token.offset + token.lexeme.len());
+        }
+        text
+    }
+
     fn match_then_advance(&mut self, ttypes: Vec<TokenType>) -> bool {
         for t in ttypes {
             if self.check(t) {
@@ -475,6 +1037,13 @@ impl Parser {
         }
     }
 
+    fn check_ahead(&self, offset: usize, ttype: TokenType) -> bool {
+        match self.tokens.get(self.current + offset) {
+            Some(token) => token.ttype == ttype,
+            None => false,
+        }
+    }
+
     fn consume(&mut self, ttype: TokenType, msg: &str) -> Result<&Token, ParserError> {
         if self.check(ttype) {
             Ok(self.advance())
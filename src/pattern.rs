@@ -0,0 +1,129 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use regex::Regex;
+
+use crate::callable::*;
+use crate::interpreter::*;
+use crate::native::{bool, list, nil, number, string, string_arg, NativeFunction};
+
+// Compiled patterns are kept so that `regex(p)` inside a loop compiles `p`
+// once. The cache is simply dropped when it fills up.
+const CACHE_SIZE: usize = 256;
+
+type RegexFn = fn(&Regex, &[CallableRet]) -> Result<CallableRet, RuntimeException>;
+
+pub fn define(interpreter: &mut Interpreter) {
+    interpreter.define_native("regex", 1, |interpreter, arguments| {
+        let pattern = string_arg(&arguments[0], "regex")?;
+        if let Some(regex) = interpreter.regexes.get(pattern) {
+            return Ok(CallableRet::Regex(regex.clone()));
+        }
+        let regex = Rc::new(compile(pattern)?);
+        if interpreter.regexes.len() == CACHE_SIZE {
+            interpreter.regexes.clear();
+        }
+        interpreter
+            .regexes
+            .insert(String::from(pattern), regex.clone());
+        Ok(CallableRet::Regex(regex))
+    });
+}
+
+// The regex crate only reports positions inside a multi-line message, so the
+// pattern is parsed with regex-syntax first to get the column on its own.
+fn compile(pattern: &str) -> Result<Regex, RuntimeException> {
+    let position = match regex_syntax::Parser::new().parse(pattern) {
+        Ok(_) => None,
+        Err(regex_syntax::Error::Parse(e)) => Some((e.span().start.column, e.kind().to_string())),
+        Err(regex_syntax::Error::Translate(e)) => {
+            Some((e.span().start.column, e.kind().to_string()))
+        }
+        Err(e) => Some((1, e.to_string())),
+    };
+    if let Some((column, msg)) = position {
+        return Err(RuntimeException::InvalidArgument(format!(
+            "Invalid regex at column {column}: {msg}."
+        )));
+    }
+    Regex::new(pattern).map_err(|e| {
+        RuntimeException::InvalidArgument(format!("Invalid regex: {}.", e.to_string().trim()))
+    })
+}
+
+// Methods are natives that capture the regex they were looked up on.
+pub fn method(regex: &Rc<Regex>, name: &str) -> Option<CallableRet> {
+    let (arity, function): (usize, RegexFn) = match name {
+        "test" => (1, |regex, arguments| {
+            Ok(bool(regex.is_match(string_arg(&arguments[0], "test")?)))
+        }),
+        "match" => (1, |regex, arguments| {
+            let text = string_arg(&arguments[0], "match")?;
+            Ok(match regex.captures(text) {
+                Some(captures) => captures_map(regex, text, &captures),
+                None => nil(),
+            })
+        }),
+        "findAll" => (1, |regex, arguments| {
+            let text = string_arg(&arguments[0], "findAll")?;
+            Ok(list(
+                regex
+                    .find_iter(text)
+                    .map(|m| string(String::from(m.as_str())))
+                    .collect(),
+            ))
+        }),
+        // The replacement may refer to groups as `$1` or `${name}`.
+        "replace" => (2, |regex, arguments| {
+            let text = string_arg(&arguments[0], "replace")?;
+            let replacement = string_arg(&arguments[1], "replace")?;
+            Ok(string(regex.replace_all(text, replacement).into_owned()))
+        }),
+        "split" => (1, |regex, arguments| {
+            let text = string_arg(&arguments[0], "split")?;
+            Ok(list(
+                regex
+                    .split(text)
+                    .map(|part| string(String::from(part)))
+                    .collect(),
+            ))
+        }),
+        _ => return None,
+    };
+    let regex = regex.clone();
+    Some(CallableRet::Callable(Callable::Native(NativeFunction {
+        name: String::from(name),
+        arity,
+        function: Rc::new(move |_, arguments| function(&regex, arguments)),
+    })))
+}
+
+// A match is a map of the matched `text`, its character `index`, the numbered
+// `groups` (nil for groups that didn't take part) and the `named` groups.
+fn captures_map(regex: &Regex, text: &str, captures: &regex::Captures) -> CallableRet {
+    let whole = captures.get(0).unwrap();
+    let group = |m: Option<regex::Match>| match m {
+        Some(m) => string(String::from(m.as_str())),
+        None => nil(),
+    };
+    let groups = captures.iter().skip(1).map(group).collect();
+    let named = regex
+        .capture_names()
+        .flatten()
+        .map(|name| (String::from(name), group(captures.name(name))))
+        .collect();
+    let entries = BTreeMap::from([
+        (String::from("text"), string(String::from(whole.as_str()))),
+        (
+            String::from("index"),
+            number(text[..whole.start()].chars().count() as f64),
+        ),
+        (String::from("groups"), list(groups)),
+        (
+            String::from("named"),
+            CallableRet::Map(Rc::new(RefCell::new(named))),
+        ),
+    ]);
+    CallableRet::Map(Rc::new(RefCell::new(entries)))
+}
@@ -0,0 +1,108 @@
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+
+use crate::callable::*;
+use crate::interpreter::*;
+use crate::native::{nil, number, number_arg};
+
+// xoshiro256**, seeded through splitmix64 so that any 64-bit seed gives a
+// well-mixed state.
+pub struct Rng {
+    state: [u64; 4],
+}
+
+impl Rng {
+    pub fn from_seed(seed: u64) -> Self {
+        let mut seed = seed;
+        let mut split_mix = || {
+            seed = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = seed;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        };
+        Self {
+            state: [split_mix(), split_mix(), split_mix(), split_mix()],
+        }
+    }
+
+    // The standard library's hash keys are drawn from the OS's random source.
+    pub fn from_os() -> Self {
+        Self::from_seed(RandomState::new().hash_one(0u64))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let [s0, s1, s2, s3] = &mut self.state;
+        let result = s1.wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = *s1 << 17;
+        *s2 ^= *s0;
+        *s3 ^= *s1;
+        *s1 ^= *s2;
+        *s0 ^= *s3;
+        *s2 ^= t;
+        *s3 = s3.rotate_left(45);
+        result
+    }
+
+    // Uniform in [0, 1), using the top 53 bits.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // Uniform in [0, bound), rejecting the values that would bias the modulo.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        let limit = u64::MAX - u64::MAX % bound;
+        loop {
+            let n = self.next_u64();
+            if n < limit {
+                return n % bound;
+            }
+        }
+    }
+}
+
+pub fn define(interpreter: &mut Interpreter) {
+    interpreter.define_native("random", 0, |interpreter, _| {
+        Ok(number(interpreter.rng.next_f64()))
+    });
+    // Both bounds are inclusive.
+    interpreter.define_native("randomInt", 2, |interpreter, arguments| {
+        let lo = number_arg(&arguments[0], "randomInt")?;
+        let hi = number_arg(&arguments[1], "randomInt")?;
+        if lo.fract() != 0.0 || hi.fract() != 0.0 || lo > hi || hi - lo >= u64::MAX as f64 {
+            return Err(RuntimeException::InvalidArgument(String::from(
+                "randomInt() bounds must be integers with lo <= hi.",
+            )));
+        }
+        let offset = interpreter.rng.below((hi - lo) as u64 + 1);
+        Ok(number(lo + offset as f64))
+    });
+    interpreter.define_native("shuffle", 1, |interpreter, arguments| {
+        let CallableRet::List(list) = &arguments[0] else {
+            return Err(RuntimeException::InvalidArgument(String::from(
+                "Argument to shuffle() must be a list.",
+            )));
+        };
+        let mut list = list.borrow_mut();
+        for i in (1..list.len()).rev() {
+            let j = interpreter.rng.below(i as u64 + 1) as usize;
+            list.swap(i, j);
+        }
+        Ok(nil())
+    });
+    interpreter.define_native("choice", 1, |interpreter, arguments| {
+        let CallableRet::List(list) = &arguments[0] else {
+            return Err(RuntimeException::InvalidArgument(String::from(
+                "Argument to choice() must be a list.",
+            )));
+        };
+        let list = list.borrow();
+        if list.is_empty() {
+            return Err(RuntimeException::InvalidArgument(String::from(
+                "Can't choose from an empty list.",
+            )));
+        }
+        let i = interpreter.rng.below(list.len() as u64) as usize;
+        Ok(list[i].clone())
+    });
+}
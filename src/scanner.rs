@@ -25,12 +25,12 @@ enum ScannerError {
 
 #[inline]
 fn is_digit(c: char) -> bool {
-    c >= '0' && c <= '9'
+    c.is_ascii_digit()
 }
 
 #[inline]
 pub fn is_alpha(c: char) -> bool {
-    c >= 'a' && c <= 'z' || c >= 'A' && c <= 'Z' || c == '_'
+    c.is_ascii_lowercase() || c.is_ascii_uppercase() || c == '_'
 }
 
 #[inline]
@@ -84,6 +84,10 @@ impl<'a> Scanner<'a> {
             ')' => self.add_token(TokenType::RIGHT_PAREN),
             '{' => self.add_token(TokenType::LEFT_BRACE),
             '}' => self.add_token(TokenType::RIGHT_BRACE),
+            '[' => self.add_token(TokenType::LEFT_BRACKET),
+            ']' => self.add_token(TokenType::RIGHT_BRACKET),
+            ':' => self.add_token(TokenType::COLON),
+            '@' => self.add_token(TokenType::AT),
             ',' => self.add_token(TokenType::COMMA),
             '.' => self.add_token(TokenType::DOT),
             '-' => self.add_token(TokenType::MINUS),
@@ -131,6 +135,7 @@ impl<'a> Scanner<'a> {
             '"' => return self.string(),
             c if is_digit(c) => self.number(),
             c if is_alpha(c) => self.identifier(),
+            '#' if is_alpha(self.peek()) => self.identifier(),
             '\n' => self.line += 1,
             _ => return Err(ScannerError::UnknownChar(self.line, c)),
         };
@@ -197,7 +202,7 @@ impl<'a> Scanner<'a> {
             self.advance();
         }
         let text = &self.source[self.start..self.current];
-        let ttype: TokenType = KEYWORDS.get(text).unwrap_or(&TokenType::IDENTIFIER).clone();
+        let ttype: TokenType = *KEYWORDS.get(text).unwrap_or(&TokenType::IDENTIFIER);
         self.add_token(ttype);
     }
 
@@ -210,7 +215,7 @@ impl<'a> Scanner<'a> {
             TokenType::EOF => String::from(""),
             _ => String::from(&self.source[self.start..self.current]),
         };
-        let token = Token::new(ttype, self.line, lexeme, None);
+        let token = Token::new(ttype, self.line, self.start, lexeme, None);
         self.tokens.push(token);
     }
 
@@ -222,6 +227,7 @@ impl<'a> Scanner<'a> {
                 let token = Token::new(
                     ttype,
                     self.line,
+                    self.start,
                     lexeme,
                     Some(LiteralValue::StringLiteral(literal)),
                 );
@@ -230,7 +236,7 @@ impl<'a> Scanner<'a> {
             TokenType::NUMBER => {
                 let lexeme = String::from(&self.source[self.start..self.current]);
                 let num_literal = Some(LiteralValue::NumberLiteral(str::parse(&literal).unwrap()));
-                let token = Token::new(ttype, self.line, lexeme, num_literal);
+                let token = Token::new(ttype, self.line, self.start, lexeme, num_literal);
                 self.tokens.push(token);
             }
             _ => unimplemented!(),
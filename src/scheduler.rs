@@ -0,0 +1,197 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::callable::*;
+use crate::generator::Generator;
+use crate::interpreter::*;
+use crate::token::LiteralValue;
+
+// Tasks are generators: a task runs until it yields, and what it yields tells
+// the scheduler when to resume it. `yield sleep(ms);` parks it until the clock
+// reaches the wake time, `var v = yield ch.recv();` parks it until a value is
+// sent on the channel, and any other value just lets the other tasks run.
+pub struct Scheduler {
+    // Milliseconds, like the durations passed to `sleep`.
+    fake_time: Option<f64>,
+    ready: VecDeque<(Task, CallableRet)>,
+    sleeping: Vec<(f64, Task)>,
+    // Set while a task is being stepped; `sleep` and `recv` are only
+    // meaningful there, since nothing else acts on what they return.
+    in_task: bool,
+    // Tasks parked on a channel's receivers; any left when nothing else can
+    // run will never wake up.
+    blocked: usize,
+}
+
+pub enum Task {
+    Start(Callable),
+    Running(Rc<RefCell<Generator>>),
+}
+
+#[derive(Clone)]
+pub enum Await {
+    Sleep(f64),
+    Recv(Rc<RefCell<Channel>>),
+}
+
+#[derive(Default)]
+pub struct Channel {
+    buffer: VecDeque<CallableRet>,
+    receivers: VecDeque<Rc<RefCell<Generator>>>,
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self {
+            fake_time: None,
+            ready: VecDeque::new(),
+            sleeping: Vec::new(),
+            in_task: false,
+            blocked: 0,
+        }
+    }
+
+    // With a fake clock, time starts at zero and only moves forward when every
+    // task is asleep, so runs are reproducible.
+    pub fn use_fake_clock(&mut self) {
+        self.fake_time = Some(0.0);
+    }
+
+    pub fn now(&self) -> f64 {
+        self.now_ms() / 1000.0
+    }
+
+    fn now_ms(&self) -> f64 {
+        match self.fake_time {
+            Some(time) => time,
+            None => {
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .expect("system time earlier than unix epoch")
+                    .as_secs_f64()
+                    * 1000.0
+            }
+        }
+    }
+
+    pub fn in_task(&self) -> bool {
+        self.in_task
+    }
+
+    pub fn spawn(&mut self, task: Task) {
+        self.ready
+            .push_back((task, CallableRet::Value(LiteralValue::NilLiteral)));
+    }
+
+    fn sleep(&mut self, task: Task, ms: f64) {
+        let wake = self.now_ms() + ms.max(0.0);
+        let at = self.sleeping.partition_point(|(other, _)| *other <= wake);
+        self.sleeping.insert(at, (wake, task));
+    }
+
+    fn wake_due(&mut self) {
+        let now = self.now_ms();
+        let due = self.sleeping.partition_point(|(wake, _)| *wake <= now);
+        for (_, task) in self.sleeping.drain(..due) {
+            self.ready
+                .push_back((task, CallableRet::Value(LiteralValue::NilLiteral)));
+        }
+    }
+
+    // Returns false once no task can ever run again.
+    fn advance(&mut self) -> bool {
+        let Some((wake, _)) = self.sleeping.first() else {
+            return false;
+        };
+        let wake = *wake;
+        match &mut self.fake_time {
+            Some(time) => *time = time.max(wake),
+            None => {
+                let wait = wake - self.now_ms();
+                if wait > 0.0 {
+                    thread::sleep(Duration::from_secs_f64(wait / 1000.0));
+                }
+            }
+        }
+        true
+    }
+
+    pub fn run(interpreter: &mut Interpreter) -> Result<(), RuntimeException> {
+        loop {
+            interpreter.scheduler.wake_due();
+            match interpreter.scheduler.ready.pop_front() {
+                Some((task, sent)) => Self::step(interpreter, task, sent)?,
+                None if interpreter.scheduler.advance() => {}
+                None if interpreter.scheduler.blocked > 0 => {
+                    return Err(RuntimeException::Deadlock(interpreter.scheduler.blocked))
+                }
+                None => return Ok(()),
+            }
+        }
+    }
+
+    fn step(
+        interpreter: &mut Interpreter,
+        task: Task,
+        sent: CallableRet,
+    ) -> Result<(), RuntimeException> {
+        interpreter.scheduler.in_task = true;
+        let result = Self::resume(interpreter, task, sent);
+        interpreter.scheduler.in_task = false;
+        result
+    }
+
+    fn resume(
+        interpreter: &mut Interpreter,
+        task: Task,
+        sent: CallableRet,
+    ) -> Result<(), RuntimeException> {
+        let generator = match task {
+            Task::Start(mut callable) => match callable.call(interpreter, &[])? {
+                CallableRet::Generator(generator) => generator,
+                _ => return Ok(()),
+            },
+            Task::Running(generator) => generator,
+        };
+        let Some(yielded) = Generator::send(&generator, interpreter, sent)? else {
+            return Ok(());
+        };
+        let scheduler = &mut interpreter.scheduler;
+        match yielded {
+            CallableRet::Await(Await::Sleep(ms)) => scheduler.sleep(Task::Running(generator), ms),
+            CallableRet::Await(Await::Recv(channel)) => {
+                let mut channel = channel.borrow_mut();
+                match channel.buffer.pop_front() {
+                    Some(value) => scheduler.ready.push_back((Task::Running(generator), value)),
+                    None => {
+                        channel.receivers.push_back(generator);
+                        scheduler.blocked += 1;
+                    }
+                }
+            }
+            _ => scheduler.spawn(Task::Running(generator)),
+        }
+        Ok(())
+    }
+}
+
+impl Channel {
+    pub fn send(&mut self, scheduler: &mut Scheduler, value: CallableRet) {
+        match self.receivers.pop_front() {
+            Some(receiver) => {
+                scheduler.blocked -= 1;
+                scheduler.ready.push_back((Task::Running(receiver), value));
+            }
+            None => self.buffer.push_back(value),
+        }
+    }
+}
@@ -11,6 +11,13 @@ pub enum Stmt {
     WhileStmt(WhileStmtInner),
     FunctionStmt(FunctionStmtInner),
     ReturnStmt(ReturnStmtInner),
+    YieldStmt(YieldStmtInner),
+    ForInStmt(ForInStmtInner),
+    AssertStmt(AssertStmtInner),
+    DeferStmt(DeferStmtInner),
+    EnumStmt(EnumStmtInner),
+    ClassStmt(ClassStmtInner),
+    TraitStmt(TraitStmtInner),
 }
 
 #[derive(Clone)]
@@ -18,7 +25,7 @@ pub struct ExprStmtInner(pub Box<Expr>);
 #[derive(Clone)]
 pub struct PrintStmtInner(pub Box<Expr>);
 #[derive(Clone)]
-pub struct VarStmtInner(pub Token, pub Option<Box<Expr>>);
+pub struct VarStmtInner(pub Pattern, pub Option<Box<Expr>>, pub Option<Token>);
 #[derive(Clone)]
 pub struct BlockStmtInner(pub Vec<Box<Stmt>>);
 #[derive(Clone)]
@@ -36,13 +43,69 @@ pub struct WhileStmtInner {
 pub struct FunctionStmtInner {
     pub name: Token,
     pub params: Vec<Token>,
+    pub param_types: Vec<Option<Token>>,
+    pub return_type: Option<Token>,
     pub body: Box<Stmt>,
+    pub is_generator: bool,
+    pub decorators: Vec<Expr>,
 }
 #[derive(Clone)]
 pub struct ReturnStmtInner {
     pub keyword: Token,
     pub value: Option<Box<Expr>>,
 }
+#[derive(Clone)]
+pub struct YieldStmtInner {
+    pub keyword: Token,
+    pub value: Option<Box<Expr>>,
+    pub target: Option<YieldTarget>,
+}
+#[derive(Clone)]
+pub enum YieldTarget {
+    Declare(Token),
+    Assign(Token),
+}
+#[derive(Clone)]
+pub struct ForInStmtInner {
+    pub name: Token,
+    pub keyword: Token,
+    pub iterable: Box<Expr>,
+    pub body: Box<Stmt>,
+}
+#[derive(Clone)]
+pub struct ClassStmtInner {
+    pub name: Token,
+    pub superclass: Option<Var>,
+    pub traits: Vec<Var>,
+    pub methods: Vec<FunctionStmtInner>,
+    pub getters: Vec<FunctionStmtInner>,
+    pub setters: Vec<FunctionStmtInner>,
+    pub class_methods: Vec<FunctionStmtInner>,
+    pub private: Vec<Token>,
+}
+#[derive(Clone)]
+pub struct TraitStmtInner {
+    pub name: Token,
+    pub methods: Vec<FunctionStmtInner>,
+    pub required: Vec<Token>,
+}
+#[derive(Clone)]
+pub struct EnumStmtInner {
+    pub name: Token,
+    pub variants: Vec<Token>,
+}
+#[derive(Clone)]
+pub struct DeferStmtInner {
+    pub keyword: Token,
+    pub expr: Box<Expr>,
+}
+#[derive(Clone)]
+pub struct AssertStmtInner {
+    pub keyword: Token,
+    pub condition: Box<Expr>,
+    pub message: Option<Box<Expr>>,
+    pub source: String,
+}
 impl IfStmtInner {
     pub fn new(
         condition: Box<Expr>,
@@ -64,8 +127,23 @@ impl WhileStmtInner {
 }
 
 impl FunctionStmtInner {
-    pub fn new(name: Token, params: Vec<Token>, body: Box<Stmt>) -> Self {
-        Self { name, params, body }
+    pub fn new(
+        name: Token,
+        params: Vec<Token>,
+        param_types: Vec<Option<Token>>,
+        return_type: Option<Token>,
+        body: Box<Stmt>,
+        is_generator: bool,
+    ) -> Self {
+        Self {
+            name,
+            params,
+            param_types,
+            return_type,
+            body,
+            is_generator,
+            decorators: Vec::new(),
+        }
     }
 }
 
@@ -75,6 +153,43 @@ impl ReturnStmtInner {
     }
 }
 
+impl ForInStmtInner {
+    pub fn new(name: Token, keyword: Token, iterable: Box<Expr>, body: Box<Stmt>) -> Self {
+        Self {
+            name,
+            keyword,
+            iterable,
+            body,
+        }
+    }
+}
+
+impl AssertStmtInner {
+    pub fn new(
+        keyword: Token,
+        condition: Box<Expr>,
+        message: Option<Box<Expr>>,
+        source: String,
+    ) -> Self {
+        Self {
+            keyword,
+            condition,
+            message,
+            source,
+        }
+    }
+}
+
+impl YieldStmtInner {
+    pub fn new(keyword: Token, value: Option<Box<Expr>>, target: Option<YieldTarget>) -> Self {
+        Self {
+            keyword,
+            value,
+            target,
+        }
+    }
+}
+
 pub trait StmtVisitor<R> {
     fn visit_expr(&mut self, expr: &ExprStmtInner) -> R;
     fn visit_print(&mut self, expr: &PrintStmtInner) -> R;
@@ -84,6 +199,13 @@ pub trait StmtVisitor<R> {
     fn visit_while(&mut self, while_stmt: &WhileStmtInner) -> R;
     fn visit_function(&mut self, func_stmt: &FunctionStmtInner) -> R;
     fn visit_return(&mut self, return_stmt: &ReturnStmtInner) -> R;
+    fn visit_yield(&mut self, yield_stmt: &YieldStmtInner) -> R;
+    fn visit_for_in(&mut self, for_in: &ForInStmtInner) -> R;
+    fn visit_assert(&mut self, assert: &AssertStmtInner) -> R;
+    fn visit_defer(&mut self, defer: &DeferStmtInner) -> R;
+    fn visit_enum(&mut self, enum_stmt: &EnumStmtInner) -> R;
+    fn visit_class(&mut self, class: &ClassStmtInner) -> R;
+    fn visit_trait(&mut self, trait_stmt: &TraitStmtInner) -> R;
 }
 
 pub trait StmtAccept<R> {
@@ -101,6 +223,13 @@ impl<R> StmtAccept<R> for Stmt {
             Stmt::WhileStmt(while_stmt) => visitor.visit_while(while_stmt),
             Stmt::FunctionStmt(func_stmt) => visitor.visit_function(func_stmt),
             Stmt::ReturnStmt(return_stmt_inner) => visitor.visit_return(return_stmt_inner),
+            Stmt::YieldStmt(yield_stmt) => visitor.visit_yield(yield_stmt),
+            Stmt::ForInStmt(for_in) => visitor.visit_for_in(for_in),
+            Stmt::AssertStmt(assert) => visitor.visit_assert(assert),
+            Stmt::DeferStmt(defer) => visitor.visit_defer(defer),
+            Stmt::EnumStmt(enum_stmt) => visitor.visit_enum(enum_stmt),
+            Stmt::ClassStmt(class) => visitor.visit_class(class),
+            Stmt::TraitStmt(trait_stmt) => visitor.visit_trait(trait_stmt),
         }
     }
 }
@@ -0,0 +1,91 @@
+use crate::builtins::*;
+use crate::callable::*;
+use crate::interpreter::*;
+use crate::native::{list, nil, number, number_arg, string, string_arg};
+use crate::token::{LiteralValue, Token, TokenType};
+
+// String operations that are also available as global functions taking the
+// string first, e.g. `upper(s)` for `s.upper()`.
+const STRING_FUNCTIONS: [&str; 12] = [
+    "len",
+    "substr",
+    "indexOf",
+    "split",
+    "trim",
+    "upper",
+    "lower",
+    "replace",
+    "startsWith",
+    "endsWith",
+    "repeat",
+    "chars",
+];
+
+// Conversions between strings and other values, plus the string methods from
+// `builtins.rs` as global functions.
+pub fn define(interpreter: &mut Interpreter) {
+    for name in STRING_FUNCTIONS {
+        define_method_function(interpreter, name, string(String::new()), "a string");
+    }
+    define_method_function(interpreter, "join", list(Vec::new()), "a list");
+    interpreter.define_native("ord", 1, |_, arguments| {
+        let mut chars = match &arguments[0] {
+            CallableRet::Value(LiteralValue::StringLiteral(s)) => s.chars(),
+            _ => return Err(single_char()),
+        };
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(number(c as u32 as f64)),
+            _ => Err(single_char()),
+        }
+    });
+    interpreter.define_native("chr", 1, |_, arguments| {
+        let code = number_arg(&arguments[0], "chr")?;
+        let c = (code.fract() == 0.0 && (0.0..=u32::MAX as f64).contains(&code))
+            .then(|| char::from_u32(code as u32))
+            .flatten()
+            .ok_or_else(|| {
+                RuntimeException::InvalidArgument(format!("{code} is not a valid character code."))
+            })?;
+        Ok(string(String::from(c)))
+    });
+    // Returns nil when the string isn't a number, so callers can test for it.
+    interpreter.define_native("parseNumber", 1, |_, arguments| {
+        let s = string_arg(&arguments[0], "parseNumber")?;
+        Ok(s.trim().parse::<f64>().map_or(nil(), number))
+    });
+    interpreter.define_native("toString", 1, |_, arguments| {
+        Ok(string(arguments[0].to_string()))
+    });
+}
+
+// Defines `name(receiver, ...)` as a call to the `name` method of a receiver
+// of the same kind as `sample`.
+fn define_method_function(
+    interpreter: &mut Interpreter,
+    name: &'static str,
+    sample: CallableRet,
+    expected: &'static str,
+) {
+    let method = BuiltinMethod::lookup(&sample, name).expect("built-in method");
+    interpreter.define_native(name, method.arity() + 1, move |interpreter, arguments| {
+        if BuiltinMethod::lookup(&arguments[0], name).is_none()
+            || std::mem::discriminant(&arguments[0]) != std::mem::discriminant(&sample)
+        {
+            return Err(RuntimeException::InvalidArgument(format!(
+                "First argument to {name}() must be {expected}."
+            )));
+        }
+        let bound = BoundNative {
+            receiver: arguments[0].clone(),
+            method,
+            name: Token::new(TokenType::IDENTIFIER, 0, 0, String::from(name), None),
+        };
+        bound.call(interpreter, &arguments[1..])
+    });
+}
+
+fn single_char() -> RuntimeException {
+    RuntimeException::InvalidArgument(String::from(
+        "Argument to ord() must be a single character.",
+    ))
+}
@@ -0,0 +1,36 @@
+use std::cell::RefCell;
+use std::env;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+use crate::callable::*;
+use crate::interpreter::*;
+use crate::native::{nil, string, string_arg};
+use crate::token::LiteralValue;
+
+pub fn define(interpreter: &mut Interpreter) {
+    interpreter.define_native("args", 0, |interpreter, _| {
+        let args = interpreter.args.iter().cloned().map(string).collect();
+        Ok(CallableRet::List(Rc::new(RefCell::new(args))))
+    });
+    interpreter.define_native("getenv", 1, |_, arguments| {
+        let name = string_arg(&arguments[0], "getenv")?;
+        Ok(match env::var(name) {
+            Ok(value) => string(value),
+            Err(_) => nil(),
+        })
+    });
+    // Unwinds like an error so that deferred expressions still run and an
+    // embedder gets control back; the caller decides what exiting means.
+    interpreter.define_native("exit", 1, |_, arguments| match &arguments[0] {
+        CallableRet::Value(LiteralValue::NumberLiteral(code))
+            if code.fract() == 0.0 && (0.0..=255.0).contains(code) =>
+        {
+            let _ = io::stdout().flush();
+            Err(RuntimeException::Exit(*code as i32))
+        }
+        _ => Err(RuntimeException::InvalidArgument(String::from(
+            "exit() code must be an integer between 0 and 255.",
+        ))),
+    });
+}
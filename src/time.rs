@@ -0,0 +1,393 @@
+use std::fmt::Display;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::callable::*;
+use crate::interpreter::*;
+use crate::native::{bool, number, number_arg, string, string_arg};
+use crate::token::{LiteralValue, Token, TokenType};
+
+pub const TIME_OPERANDS: &str = "Operands must be times, or a time and a number of seconds.";
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+const WEEKDAYS: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+// An instant, as seconds since the Unix epoch, together with the UTC offset
+// its calendar fields are read in. Durations are plain numbers of seconds.
+#[derive(Clone, Copy)]
+pub struct LoxTime {
+    pub seconds: f64,
+    pub offset_minutes: i32,
+}
+
+// The calendar fields of a time at its offset.
+struct Fields {
+    year: i64,
+    month: u32,
+    day: u32,
+    hour: u32,
+    minute: u32,
+    second: u32,
+    millisecond: u32,
+    weekday: usize,
+    day_of_year: u32,
+}
+
+pub fn define(interpreter: &mut Interpreter) {
+    // Follows the scheduler's clock, so `--fake-clock` starts at the epoch.
+    interpreter.define_native("now", 0, |interpreter, _| {
+        Ok(CallableRet::Time(LoxTime {
+            seconds: interpreter.scheduler.now(),
+            offset_minutes: 0,
+        }))
+    });
+    interpreter.define_native("formatTime", 2, |_, arguments| {
+        let time = time_arg(&arguments[0], "formatTime")?;
+        let format = string_arg(&arguments[1], "formatTime")?;
+        Ok(string(time.format(format)?))
+    });
+    interpreter.define_native("parseTime", 2, |_, arguments| {
+        let text = string_arg(&arguments[0], "parseTime")?;
+        let format = string_arg(&arguments[1], "parseTime")?;
+        LoxTime::parse(text, format).map(CallableRet::Time)
+    });
+    interpreter.define_native("atOffset", 2, |_, arguments| {
+        let time = time_arg(&arguments[0], "atOffset")?;
+        let minutes = number_arg(&arguments[1], "atOffset")?;
+        if minutes.fract() != 0.0 || minutes.abs() >= 24.0 * 60.0 {
+            return Err(RuntimeException::InvalidArgument(String::from(
+                "atOffset() offset must be a whole number of minutes under a day.",
+            )));
+        }
+        Ok(CallableRet::Time(LoxTime {
+            seconds: time.seconds,
+            offset_minutes: minutes as i32,
+        }))
+    });
+}
+
+impl LoxTime {
+    fn fields(&self) -> Fields {
+        let local = self.seconds + f64::from(self.offset_minutes) * 60.0;
+        let whole = local.floor();
+        let millisecond = (((local - whole) * 1000.0).floor() as u32).min(999);
+        let whole = whole as i64;
+        let days = whole.div_euclid(86400);
+        let secs = whole.rem_euclid(86400) as u32;
+        let (year, month, day) = civil_from_days(days);
+        Fields {
+            year,
+            month,
+            day,
+            hour: secs / 3600,
+            minute: secs / 60 % 60,
+            second: secs % 60,
+            millisecond,
+            // 1970-01-01 was a Thursday.
+            weekday: (days + 3).rem_euclid(7) as usize,
+            day_of_year: (days - days_from_civil(year, 1, 1)) as u32 + 1,
+        }
+    }
+
+    pub fn get(&self, name: &Token) -> Result<CallableRet, RuntimeException> {
+        let fields = self.fields();
+        let value = match name.lexeme.as_str() {
+            "year" => fields.year as f64,
+            "month" => f64::from(fields.month),
+            "day" => f64::from(fields.day),
+            "hour" => f64::from(fields.hour),
+            "minute" => f64::from(fields.minute),
+            "second" => f64::from(fields.second),
+            "millisecond" => f64::from(fields.millisecond),
+            // 1 is Monday and 7 is Sunday, as in ISO 8601.
+            "weekday" => fields.weekday as f64 + 1.0,
+            "dayOfYear" => f64::from(fields.day_of_year),
+            "timestamp" => self.seconds,
+            "offset" => f64::from(self.offset_minutes),
+            _ => return Err(RuntimeException::UndefinedProperty(name.clone())),
+        };
+        Ok(number(value))
+    }
+
+    fn format(&self, format: &str) -> Result<String, RuntimeException> {
+        let fields = self.fields();
+        let mut out = String::new();
+        let mut chars = format.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                out.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('Y') => out.push_str(&format!("{:04}", fields.year)),
+                Some('m') => out.push_str(&format!("{:02}", fields.month)),
+                Some('d') => out.push_str(&format!("{:02}", fields.day)),
+                Some('H') => out.push_str(&format!("{:02}", fields.hour)),
+                Some('M') => out.push_str(&format!("{:02}", fields.minute)),
+                Some('S') => out.push_str(&format!("{:02}", fields.second)),
+                Some('L') => out.push_str(&format!("{:03}", fields.millisecond)),
+                Some('j') => out.push_str(&format!("{:03}", fields.day_of_year)),
+                Some('a') => out.push_str(&WEEKDAYS[fields.weekday][..3]),
+                Some('A') => out.push_str(WEEKDAYS[fields.weekday]),
+                Some('b') => out.push_str(&MONTHS[fields.month as usize - 1][..3]),
+                Some('B') => out.push_str(MONTHS[fields.month as usize - 1]),
+                Some('z') => out.push_str(&self.offset_string()),
+                Some('%') => out.push('%'),
+                Some(other) => {
+                    return Err(RuntimeException::InvalidArgument(format!(
+                        "Unknown time format specifier '%{other}'."
+                    )))
+                }
+                None => {
+                    return Err(RuntimeException::InvalidArgument(String::from(
+                        "Time format can't end with '%'.",
+                    )))
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    fn offset_string(&self) -> String {
+        let sign = if self.offset_minutes < 0 { '-' } else { '+' };
+        let minutes = self.offset_minutes.abs();
+        format!("{sign}{:02}:{:02}", minutes / 60, minutes % 60)
+    }
+
+    // Fields missing from the format default to the start of their range, and
+    // the offset to UTC.
+    fn parse(text: &str, format: &str) -> Result<Self, RuntimeException> {
+        let error = |msg: &str| {
+            RuntimeException::InvalidArgument(format!(
+                "Can't parse time '{text}' with format '{format}': {msg}."
+            ))
+        };
+        let (mut year, mut month, mut day) = (1970, 1, 1);
+        let (mut hour, mut minute, mut second, mut millisecond) = (0, 0, 0, 0);
+        let mut offset_minutes = 0;
+        let mut input = text.chars().peekable();
+        let mut spec = format.chars();
+        while let Some(c) = spec.next() {
+            if c != '%' {
+                if input.next() != Some(c) {
+                    return Err(error(&format!("expected '{c}'")));
+                }
+                continue;
+            }
+            let specifier = spec.next();
+            let (field, max) = match specifier {
+                Some('Y') => (&mut year, 4),
+                Some('m') => (&mut month, 2),
+                Some('d') => (&mut day, 2),
+                Some('H') => (&mut hour, 2),
+                Some('M') => (&mut minute, 2),
+                Some('S') => (&mut second, 2),
+                Some('L') => (&mut millisecond, 3),
+                Some('z') => {
+                    offset_minutes = parse_offset(&mut input).ok_or_else(|| error("bad offset"))?;
+                    continue;
+                }
+                Some('%') => {
+                    if input.next() != Some('%') {
+                        return Err(error("expected '%'"));
+                    }
+                    continue;
+                }
+                Some(other) => return Err(error(&format!("unknown specifier '%{other}'"))),
+                None => return Err(error("format ends with '%'")),
+            };
+            *field = digits(&mut input, max).ok_or_else(|| error("expected digits"))?;
+        }
+        if input.next().is_some() {
+            return Err(error("unexpected text at the end"));
+        }
+        if !(1..=12).contains(&month)
+            || day < 1
+            || day > days_in_month(year, month as u32)
+            || hour > 23
+            || minute > 59
+            || second > 59
+        {
+            return Err(error("date or time out of range"));
+        }
+        let days = days_from_civil(year, month as u32, day as u32);
+        let local = days * 86400 + hour * 3600 + minute * 60 + second;
+        Ok(Self {
+            seconds: (local - i64::from(offset_minutes) * 60) as f64 + millisecond as f64 / 1000.0,
+            offset_minutes,
+        })
+    }
+}
+
+impl Display for LoxTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let fields = self.fields();
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            fields.year, fields.month, fields.day, fields.hour, fields.minute, fields.second
+        )?;
+        if fields.millisecond != 0 {
+            write!(f, ".{:03}", fields.millisecond)?;
+        }
+        if self.offset_minutes == 0 {
+            write!(f, "Z")
+        } else {
+            write!(f, "{}", self.offset_string())
+        }
+    }
+}
+
+// Adding or subtracting seconds moves a time, subtracting two times gives
+// the seconds between them, and times compare by instant.
+pub fn binary(
+    operator: &Token,
+    left: &CallableRet,
+    right: &CallableRet,
+) -> Option<Result<CallableRet, RuntimeException>> {
+    let seconds = |value: &CallableRet| match value {
+        CallableRet::Value(LiteralValue::NumberLiteral(n)) => Some(*n),
+        _ => None,
+    };
+    let moved = |time: &LoxTime, by: f64| {
+        Ok(CallableRet::Time(LoxTime {
+            seconds: time.seconds + by,
+            offset_minutes: time.offset_minutes,
+        }))
+    };
+    let compare = |result: bool| Ok(bool(result));
+    let result = match (left, right, operator.ttype) {
+        (CallableRet::Time(l), CallableRet::Time(r), ttype) => match ttype {
+            TokenType::MINUS => Ok(number(l.seconds - r.seconds)),
+            TokenType::LESS => compare(l.seconds < r.seconds),
+            TokenType::LESS_EQUAL => compare(l.seconds <= r.seconds),
+            TokenType::GREATER => compare(l.seconds > r.seconds),
+            TokenType::GREATER_EQUAL => compare(l.seconds >= r.seconds),
+            TokenType::EQUAL_EQUAL | TokenType::BANG_EQUAL => return None,
+            _ => Err(time_operands(operator)),
+        },
+        (CallableRet::Time(time), other, TokenType::PLUS)
+        | (other, CallableRet::Time(time), TokenType::PLUS) => match seconds(other) {
+            Some(by) => moved(time, by),
+            None => Err(time_operands(operator)),
+        },
+        (CallableRet::Time(time), other, TokenType::MINUS) => match seconds(other) {
+            Some(by) => moved(time, -by),
+            None => Err(time_operands(operator)),
+        },
+        (CallableRet::Time(_), _, _) | (_, CallableRet::Time(_), _) => match operator.ttype {
+            TokenType::EQUAL_EQUAL | TokenType::BANG_EQUAL => return None,
+            _ => Err(time_operands(operator)),
+        },
+        _ => return None,
+    };
+    Some(result)
+}
+
+fn time_operands(operator: &Token) -> RuntimeException {
+    RuntimeException::InvalidOperand(operator.ttype, String::from(TIME_OPERANDS), operator.line)
+}
+
+fn digits(input: &mut Peekable<Chars>, max: usize) -> Option<i64> {
+    let mut value = 0;
+    let mut count = 0;
+    while count < max {
+        let Some(digit) = input.peek().and_then(|c| c.to_digit(10)) else {
+            break;
+        };
+        value = value * 10 + i64::from(digit);
+        count += 1;
+        input.next();
+    }
+    (count > 0).then_some(value)
+}
+
+fn parse_offset(input: &mut Peekable<Chars>) -> Option<i32> {
+    let sign = match input.next()? {
+        'Z' => return Some(0),
+        '+' => 1,
+        '-' => -1,
+        _ => return None,
+    };
+    let two_digits = |input: &mut Peekable<Chars>| {
+        let tens = input.next()?.to_digit(10)?;
+        let ones = input.next()?.to_digit(10)?;
+        Some((tens * 10 + ones) as i32)
+    };
+    let hours = two_digits(input)?;
+    if input.peek() == Some(&':') {
+        input.next();
+    }
+    let minutes = two_digits(input)?;
+    (hours < 24 && minutes < 60).then_some(sign * (hours * 60 + minutes))
+}
+
+fn time_arg(argument: &CallableRet, function: &str) -> Result<LoxTime, RuntimeException> {
+    match argument {
+        CallableRet::Time(time) => Ok(*time),
+        _ => Err(RuntimeException::InvalidArgument(format!(
+            "First argument to {function}() must be a time."
+        ))),
+    }
+}
+
+fn is_leap(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i64, month: u32) -> i64 {
+    match month {
+        2 if is_leap(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Days since 1970-01-01 in the proleptic Gregorian calendar, after Howard
+// Hinnant's `days_from_civil`.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month = i64::from(month);
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
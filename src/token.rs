@@ -5,21 +5,28 @@ use std::fmt;
 lazy_static! {
     pub static ref KEYWORDS: HashMap<&'static str, TokenType> = HashMap::from([
         ("and", TokenType::AND),
+        ("assert", TokenType::ASSERT),
         ("class", TokenType::CLASS),
+        ("defer", TokenType::DEFER),
         ("else", TokenType::ELSE),
+        ("enum", TokenType::ENUM),
         ("false", TokenType::FALSE),
         ("for", TokenType::FOR),
         ("fun", TokenType::FUN),
         ("if", TokenType::IF),
+        ("in", TokenType::IN),
         ("nil", TokenType::NIL),
         ("or", TokenType::OR),
         ("print", TokenType::PRINT),
         ("return", TokenType::RETURN),
         ("super", TokenType::SUPER),
         ("this", TokenType::THIS),
+        ("trait", TokenType::TRAIT),
         ("true", TokenType::TRUE),
         ("var", TokenType::VAR),
         ("while", TokenType::WHILE),
+        ("with", TokenType::WITH),
+        ("yield", TokenType::YIELD),
     ]);
 }
 
@@ -31,6 +38,9 @@ pub enum TokenType {
     RIGHT_PAREN,
     LEFT_BRACE,
     RIGHT_BRACE,
+    LEFT_BRACKET,
+    RIGHT_BRACKET,
+    COLON,
     COMMA,
     DOT,
     MINUS,
@@ -38,6 +48,7 @@ pub enum TokenType {
     SEMICOLON,
     SLASH,
     STAR,
+    AT,
 
     // One or two character tokens.
     BANG,
@@ -56,21 +67,28 @@ pub enum TokenType {
 
     // Keywords.
     AND,
+    ASSERT,
     CLASS,
+    DEFER,
     ELSE,
+    ENUM,
     FALSE,
     FUN,
     FOR,
     IF,
+    IN,
     NIL,
     OR,
     PRINT,
     RETURN,
     SUPER,
     THIS,
+    TRAIT,
     TRUE,
     VAR,
     WHILE,
+    WITH,
+    YIELD,
 
     EOF,
 }
@@ -85,6 +103,7 @@ impl fmt::Display for TokenType {
 pub struct Token {
     pub ttype: TokenType,
     pub line: usize,
+    pub offset: usize,
     pub lexeme: String,
     pub literal: Option<LiteralValue>,
 }
@@ -101,12 +120,14 @@ impl Token {
     pub fn new(
         ttype: TokenType,
         line: usize,
+        offset: usize,
         lexeme: String,
         literal: Option<LiteralValue>,
     ) -> Self {
         Token {
             ttype,
             line,
+            offset,
             lexeme,
             literal,
         }
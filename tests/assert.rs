@@ -0,0 +1,47 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn passing_assertions_do_nothing() {
+    let out = run_ok("assert 1 < 2;\nassert true, \"unused\";\nprint \"ok\";");
+    assert_eq!(out, "ok\n");
+}
+
+#[test]
+fn failed_comparison_shows_source_and_operands() {
+    let err = run_err("var a = 1;\nassert a + 1 == 3, \"math is hard\";");
+    assert_eq!(
+        err,
+        "Assertion failed: a + 1 == 3, math is hard\nleft: 2, right: 3\n[line 2]\n"
+    );
+}
+
+#[test]
+fn string_operands_are_quoted() {
+    let err = run_err("assert \"a\" == \"b\";");
+    assert!(err.contains("left: \"a\", right: \"b\""), "{err}");
+}
+
+#[test]
+fn failed_plain_assertion_shows_source() {
+    let err = run_err("print \"before\";\nassert nil;");
+    assert_eq!(err, "Assertion failed: nil\n[line 2]\n");
+}
+
+#[test]
+fn message_is_only_evaluated_on_failure() {
+    let out = run_ok("assert true, nope;\nprint \"ok\";");
+    assert_eq!(out, "ok\n");
+}
+
+#[test]
+fn missing_semicolon_is_a_parse_error() {
+    let output = run("assert 1 < 2\n");
+    assert_eq!(output.code, 65);
+    assert!(
+        output.stderr.contains("expect ';' after assertion."),
+        "{}",
+        output.stderr
+    );
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn string_methods() {
+    let out = run_ok(
+        "var s = \" Hello, World \";
+         print s.trim().upper();
+         print s.len();
+         print \"a,b,c\".split(\",\");
+         print \"abc\".contains(\"b\");
+         print \"hello\".substr(1, 3);
+         print \"hello\".indexOf(\"l\");
+         print \"hello\".replace(\"l\", \"L\");
+         print \"héllo\".chars();",
+    );
+    assert_eq!(
+        out,
+        "HELLO, WORLD\n14\n[\"a\", \"b\", \"c\"]\ntrue\nell\n2\nheLLo\n[\"h\", \"é\", \"l\", \"l\", \"o\"]\n"
+    );
+}
+
+#[test]
+fn number_methods() {
+    let out = run_ok(
+        "print (3.7).floor();
+         print (3.2).ceil();
+         print (-2.5).abs();
+         print (3.14159).toFixed(2);",
+    );
+    assert_eq!(out, "3\n4\n2.5\n3.14\n");
+}
+
+#[test]
+fn list_methods() {
+    let out = run_ok(
+        "fun double(x) { return x * 2; }
+         fun big(x) { return x > 1; }
+         var xs = [1, 2, 3];
+         xs.push(4);
+         print xs.pop();
+         print xs.map(double);
+         print xs.filter(big);
+         print xs.join(\"-\");",
+    );
+    assert_eq!(out, "4\n[2, 4, 6]\n[2, 3]\n1-2-3\n");
+}
+
+#[test]
+fn map_methods() {
+    let out = run_ok(
+        "var m = {\"a\": 1, \"b\": 2};
+         print m.keys();
+         print m.has(\"a\");
+         print m.remove(\"a\");
+         print m;",
+    );
+    assert_eq!(out, "[\"a\", \"b\"]\ntrue\n1\n{\"b\": 2}\n");
+}
+
+#[test]
+fn methods_stay_bound_to_their_receiver() {
+    let out = run_ok("var up = \"abc\".upper;\nprint up;\nprint up();");
+    assert_eq!(out, "<native fn>\nABC\n");
+}
+
+#[test]
+fn unknown_method_is_an_error() {
+    let err = run_err("print \"abc\".nope();");
+    assert_eq!(err, "Undefined property 'nope'.\n[line 1]\n");
+}
+
+#[test]
+fn pop_from_empty_list_is_an_error() {
+    let err = run_err("print [].pop();");
+    assert!(err.starts_with("Can't pop from an empty list."), "{err}");
+}
+
+#[test]
+fn method_argument_errors() {
+    let err = run_err("print \"abc\".upper(1);");
+    assert_eq!(err, "Expected 0 arguments but got 1.\n");
+    let err = run_err("print \"abc\".split(1);");
+    assert_eq!(err, "Argument to split() must be a string.\n[line 1]\n");
+    let err = run_err("print (1.5).toFixed(-1);");
+    assert_eq!(
+        err,
+        "toFixed() digits must be an integer between 0 and 100.\n[line 1]\n"
+    );
+}
@@ -0,0 +1,132 @@
+mod common;
+
+use common::*;
+
+fn check(source: &str) -> Output {
+    command("check", &[], source, &[])
+}
+
+#[test]
+fn well_typed_code_passes() {
+    let out = check(
+        "var n: Number = 1;
+         fun add(a: Number, b: Number): Number { return a + b; }
+         print add(n, 2) + 3;",
+    );
+    assert_eq!(out.code, 0, "{}", out.stderr);
+}
+
+#[test]
+fn operand_errors_match_the_runtime_wording() {
+    let out = check("print \"a\" - 1;");
+    assert_eq!(out.code, 65);
+    assert_eq!(out.stderr, "Operands must be a number.\n[line 1]\n");
+    assert_eq!(run_err("print \"a\" - 1;"), out.stderr);
+}
+
+#[test]
+fn infers_types_of_unannotated_variables() {
+    let out = check("var s = \"a\";\nprint s + 1;");
+    assert_eq!(
+        out.stderr,
+        "Operands must be two numbers or two strings.\n[line 2]\n"
+    );
+}
+
+#[test]
+fn reports_annotation_mismatches() {
+    let out = check("var n: Number = \"x\";");
+    assert_eq!(
+        out.stderr,
+        "Can't assign String to 'n' of type Number.\n[line 1]\n"
+    );
+    let out = check("fun add(a: Number, b: Number): Number { return a + b; }\nadd(1, \"2\");");
+    assert_eq!(
+        out.stderr,
+        "Argument 2 must be Number but got String.\n[line 2]\n"
+    );
+    let out = check("fun f(a: Number): String { return a; }");
+    assert_eq!(
+        out.stderr,
+        "Expected a return value of type String but got Number.\n[line 1]\n"
+    );
+}
+
+#[test]
+fn calling_a_non_callable_is_reported() {
+    let out = check("var x = 1;\nx();");
+    assert_eq!(
+        out.stderr,
+        "Can only call functions and classes.\n[line 2]\n"
+    );
+}
+
+// Each program is checked and then run, since the checker must never reject
+// a program that runs fine.
+fn check_and_run(source: &str) -> String {
+    let out = check(source);
+    assert_eq!(out.code, 0, "{}", out.stderr);
+    run_ok(source)
+}
+
+#[test]
+fn variables_reassigned_later_in_a_while_body() {
+    let out = check_and_run(
+        "var x = nil; var i = 0; while (i < 2) { if (i > 0) print x - 1; x = 5; i = i + 1; }",
+    );
+    assert_eq!(out, "4\n");
+}
+
+#[test]
+fn variables_reassigned_later_in_a_for_in_body() {
+    let out =
+        check_and_run("var s = \"a\"; for (var k in [1,2]) { if (k == 2) print s * 2; s = 3; }");
+    assert_eq!(out, "6\n");
+}
+
+#[test]
+fn variables_reassigned_later_in_a_for_body() {
+    let out = check_and_run(
+        "var s = \"a\";
+         for (var i = 0; i < 2; i = i + 1) { if (i == 1) print s * 2; s = 3; }",
+    );
+    assert_eq!(out, "6\n");
+}
+
+#[test]
+fn types_widen_through_chains_of_assignments() {
+    let out = check_and_run(
+        "var a = \"a\"; var b = \"b\"; var i = 0;
+         while (i < 3) { if (i == 2) print b * 2; b = a; a = 1; i = i + 1; }",
+    );
+    assert_eq!(out, "2\n");
+}
+
+#[test]
+fn errors_in_loop_bodies_are_reported_once() {
+    let out = check("var s = \"a\";\nvar i = 0;\nwhile (i < 2) { print s - 1; i = i + 1; }");
+    assert_eq!(out.code, 65);
+    assert_eq!(out.stderr, "Operands must be a number.\n[line 3]\n");
+}
+
+#[test]
+fn annotated_variables_keep_their_type_in_loops() {
+    let out = check("var n: Number = 1;\nwhile (n < 3) { n = \"x\"; }");
+    assert_eq!(
+        out.stderr,
+        "Can't assign String to 'n' of type Number.\n[line 2]\n"
+    );
+}
+
+#[test]
+fn unknown_annotation_is_an_error() {
+    let out = check("var n: Numbr = 1;");
+    assert_eq!(out.code, 65);
+    assert_eq!(out.stderr, "Unknown type 'Numbr'.\n[line 1]\n");
+}
+
+#[test]
+fn arity_mismatches_are_reported() {
+    let out = check("fun f(a) {}\nf(1, 2);");
+    assert_eq!(out.stderr, "Expected 1 arguments but got 2.\n[line 2]\n");
+}
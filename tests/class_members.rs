@@ -0,0 +1,64 @@
+mod common;
+
+use common::*;
+
+const CIRCLE: &str = "class Circle {
+  init(r) { this.r = r; }
+  area { return 3 * this.r * this.r; }
+  radius { return this.r; }
+  set radius(v) { this.r = v; }
+  class unit() { return Circle(1); }
+  class count() { Circle.made = Circle.made + 1; return Circle.made; }
+}
+";
+
+#[test]
+fn getters_run_on_property_access() {
+    let out = run_ok(&format!("{CIRCLE}print Circle(2).area;"));
+    assert_eq!(out, "12\n");
+}
+
+#[test]
+fn setters_run_on_assignment() {
+    let out = run_ok(&format!(
+        "{CIRCLE}var c = Circle(2);\nc.radius = 5;\nprint c.r;\nprint c.radius;"
+    ));
+    assert_eq!(out, "5\n5\n");
+}
+
+#[test]
+fn static_methods_and_state_live_on_the_class() {
+    let out = run_ok(&format!(
+        "{CIRCLE}Circle.made = 0;
+         print Circle.unit().area;
+         print Circle.count();
+         print Circle.count();
+         print Circle.made;"
+    ));
+    assert_eq!(out, "3\n1\n2\n2\n");
+}
+
+#[test]
+fn static_methods_bind_this_to_the_class() {
+    let out = run_ok("class A { class me() { return this; } }\nprint A.me();");
+    assert_eq!(out, "A\n");
+}
+
+#[test]
+fn static_methods_are_not_on_instances() {
+    let err = run_err("class A { class s() {} }\nprint A().s();");
+    assert_eq!(err, "Undefined property 's'.\n[line 2]\n");
+}
+
+#[test]
+fn setter_arity_is_checked_by_the_parser() {
+    let output = run("class A { set x() {} }");
+    assert_eq!(output.code, 65);
+    assert!(
+        output
+            .stderr
+            .contains("A setter must take exactly one parameter."),
+        "{}",
+        output.stderr
+    );
+}
@@ -0,0 +1,89 @@
+#![allow(dead_code)]
+
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub struct Output {
+    pub stdout: String,
+    pub stderr: String,
+    pub code: i32,
+}
+
+static SCRIPTS: AtomicUsize = AtomicUsize::new(0);
+
+// Writes `source` to a fresh file in the temp directory and returns its path.
+pub fn script(source: &str) -> PathBuf {
+    let n = SCRIPTS.fetch_add(1, Ordering::SeqCst);
+    let path = std::env::temp_dir().join(format!("lox-test-{}-{n}.lox", std::process::id()));
+    fs::write(&path, source).unwrap();
+    path
+}
+
+// Runs the interpreter binary as `<command> [flags] <script> [args]`.
+pub fn command(command: &str, flags: &[&str], source: &str, args: &[&str]) -> Output {
+    let path = script(source);
+    let output = Command::new(env!("CARGO_BIN_EXE_codecrafters-interpreter"))
+        .arg(command)
+        .args(flags)
+        .arg(&path)
+        .args(args)
+        .output()
+        .unwrap();
+    fs::remove_file(&path).unwrap();
+    Output {
+        stdout: String::from_utf8(output.stdout).unwrap(),
+        stderr: String::from_utf8(output.stderr).unwrap(),
+        code: output.status.code().unwrap(),
+    }
+}
+
+// Runs a script with `input` piped to its stdin.
+pub fn run_with_input(source: &str, input: &str) -> Output {
+    let path = script(source);
+    let mut child = Command::new(env!("CARGO_BIN_EXE_codecrafters-interpreter"))
+        .arg("run")
+        .arg(&path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    fs::remove_file(&path).unwrap();
+    Output {
+        stdout: String::from_utf8(output.stdout).unwrap(),
+        stderr: String::from_utf8(output.stderr).unwrap(),
+        code: output.status.code().unwrap(),
+    }
+}
+
+pub fn run(source: &str) -> Output {
+    command("run", &[], source, &[])
+}
+
+// Runs a script that must succeed and returns what it printed.
+pub fn run_ok(source: &str) -> String {
+    let output = run(source);
+    assert_eq!(output.code, 0, "script failed:\n{}", output.stderr);
+    output.stdout
+}
+
+// Runs a script that must fail at runtime and returns its error message.
+pub fn run_err(source: &str) -> String {
+    let output = run(source);
+    assert_eq!(
+        output.code, 70,
+        "expected a runtime error, got:\n{}",
+        output.stdout
+    );
+    output.stderr
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn decorator_replaces_the_function() {
+    let out = run_ok(
+        "fun memoize(f) {
+           var cache = {};
+           fun wrapper(n) {
+             var key = n.toFixed(0);
+             if (cache.has(key)) return cache[key];
+             var v = f(n);
+             cache[key] = v;
+             return v;
+           }
+           return wrapper;
+         }
+         var calls = 0;
+         @memoize
+         fun fib(n) { calls = calls + 1; if (n < 2) return n; return fib(n - 1) + fib(n - 2); }
+         print fib(30);
+         print calls;",
+    );
+    assert_eq!(out, "832040\n31\n");
+}
+
+#[test]
+fn decorators_apply_bottom_up() {
+    let out = run_ok(
+        "fun tag(f) { fun w(x) { return \"<\" + f(x) + \">\"; } return w; }
+         fun shout(f) { fun w(x) { return f(x) + \"!\"; } return w; }
+         @tag
+         @shout
+         fun hi(x) { return x; }
+         print hi(\"a\");",
+    );
+    assert_eq!(out, "<a!>\n");
+}
+
+#[test]
+fn decorated_methods_keep_this() {
+    let out = run_ok(
+        "fun shout(f) { fun w(x) { return f(x) + \"!\"; } return w; }
+         class A {
+           init() { this.s = \"s\"; }
+           @shout
+           name(x) { return x + this.s; }
+         }
+         print A().name(\"n\");",
+    );
+    assert_eq!(out, "ns!\n");
+}
+
+#[test]
+fn decorator_must_be_callable() {
+    let err = run_err("@1\nfun f() {}");
+    assert!(
+        err.starts_with("Can only call functions and classes."),
+        "{err}"
+    );
+}
+
+#[test]
+fn decorators_need_a_function() {
+    let output = run("@f\nvar x = 1;");
+    assert_eq!(output.code, 65);
+    assert!(
+        output.stderr.contains("expect function after decorators."),
+        "{}",
+        output.stderr
+    );
+}
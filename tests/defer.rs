@@ -0,0 +1,89 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn deferred_expressions_run_in_reverse_order() {
+    let out = run_ok(
+        "fun say(s) { print s; }
+         fun f() {
+           defer say(\"first\");
+           defer say(\"second\");
+           {
+             defer say(\"block\");
+             print \"in block\";
+           }
+           return \"ret\";
+         }
+         print f();",
+    );
+    assert_eq!(out, "in block\nblock\nsecond\nfirst\nret\n");
+}
+
+#[test]
+fn deferred_expressions_run_at_the_end_of_each_iteration() {
+    let out = run_ok(
+        "fun say(s) { print s; }
+         var i = 0;
+         while (i < 2) { defer say(i); i = i + 1; print \"body\"; }",
+    );
+    assert_eq!(out, "body\n1\nbody\n2\n");
+}
+
+#[test]
+fn deferred_expressions_run_when_an_error_unwinds() {
+    let output =
+        run("fun say(s) { print s; }\nfun g() { defer say(\"cleanup\"); print nope; }\ng();");
+    assert_eq!(output.code, 70);
+    assert_eq!(output.stdout, "cleanup\n");
+    assert!(
+        output.stderr.starts_with("Undefined variable 'nope'."),
+        "{}",
+        output.stderr
+    );
+}
+
+#[test]
+fn deferred_errors_are_attached_to_the_original_error() {
+    let err = run_err("fun f() { defer nope1; defer nope2; print nope3; }\nf();");
+    assert_eq!(
+        err,
+        "Undefined variable 'nope3'.\n\
+         Error in deferred expression: Undefined variable 'nope2'.\n\
+         Error in deferred expression: Undefined variable 'nope1'.\n"
+    );
+}
+
+#[test]
+fn deferred_error_alone_is_reported() {
+    let output = run("fun f() { defer nope; }\nf();\nprint \"after\";");
+    assert_eq!(output.code, 70);
+    assert_eq!(output.stdout, "");
+    assert!(
+        output.stderr.starts_with("Undefined variable 'nope'."),
+        "{}",
+        output.stderr
+    );
+}
+
+#[test]
+fn generators_run_pending_defers_when_they_finish_or_fail() {
+    let output = run("fun say(s) { print s; }
+         fun* g() { defer say(\"done\"); yield 1; }
+         var a = g(); a.next(); a.next();
+         fun* h() { defer say(\"failed\"); yield 1; print nope; }
+         var b = h(); b.next(); b.next();");
+    assert_eq!(output.code, 70);
+    assert_eq!(output.stdout, "done\nfailed\n");
+}
+
+#[test]
+fn dropped_generators_skip_pending_defers() {
+    let out = run_ok(
+        "fun say(s) { print s; }
+         fun* g() { defer say(\"cleanup\"); yield 1; yield 2; }
+         { var a = g(); print a.next(); }
+         print \"end\";",
+    );
+    assert_eq!(out, "1\nend\n");
+}
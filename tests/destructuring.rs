@@ -0,0 +1,49 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn destructures_lists_and_maps() {
+    let out = run_ok(
+        "var [a, [b, c]] = [1, [2, 3]];
+         var {x, y} = {\"x\": 4, \"y\": 5};
+         print a + b + c; print x * y;",
+    );
+    assert_eq!(out, "6\n20\n");
+}
+
+#[test]
+fn swaps_through_assignment() {
+    let out = run_ok("var a = 1; var b = 2; [a, b] = [b, a]; print a; print b;");
+    assert_eq!(out, "2\n1\n");
+}
+
+#[test]
+fn destructures_instance_fields_and_getters() {
+    let out = run_ok(
+        "class Point { init(x, y) { this.x = x; this.y = y; } sum { return this.x + this.y; } }
+         var {x, y, sum} = Point(3, 4);
+         print x; print y; print sum;",
+    );
+    assert_eq!(out, "3\n4\n7\n");
+}
+
+#[test]
+fn shape_mismatches_are_line_numbered() {
+    let err = run_err("var a = 1;\nvar [b, c] = [1];");
+    assert_eq!(err, "Expected 2 elements but got 1.\n[line 2]\n");
+    let err = run_err("var {z} = {\"x\": 1};");
+    assert!(err.contains("Missing key 'z' in map.\n[line 1]"), "{err}");
+    let err = run_err("var [a] = 1;");
+    assert_eq!(
+        err,
+        "Can only destructure a list with a list pattern.\n[line 1]\n"
+    );
+    let err = run_err("var {a} = [1];");
+    assert_eq!(
+        err,
+        "Can only destructure a map or an instance with a map pattern.\n[line 1]\n"
+    );
+    let err = run_err("class P {}\nvar {z} = P();");
+    assert!(err.contains("Undefined property 'z'.\n[line 2]"), "{err}");
+}
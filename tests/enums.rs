@@ -0,0 +1,58 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn variants_print_with_their_enum() {
+    let out = run_ok("enum Color { Red, Green, Blue }\nprint Color.Red;\nprint Color;");
+    assert_eq!(out, "Color.Red\n<enum Color>\n");
+}
+
+#[test]
+fn variants_compare_by_identity() {
+    let out = run_ok(
+        "enum Color { Red, Green }
+         enum Other { Red }
+         var c = Color.Red;
+         print c == Color.Red;
+         print c == Color.Green;
+         print Other.Red == Color.Red;",
+    );
+    assert_eq!(out, "true\nfalse\nfalse\n");
+}
+
+#[test]
+fn values_lists_variants_in_order() {
+    let out = run_ok(
+        "enum Color { Red, Green, Blue }
+         print Color.values();
+         for (var c in Color.values()) print c;",
+    );
+    assert_eq!(
+        out,
+        "[Color.Red, Color.Green, Color.Blue]\nColor.Red\nColor.Green\nColor.Blue\n"
+    );
+}
+
+#[test]
+fn unknown_variant_is_an_error() {
+    let err = run_err("enum Color { Red }\nprint Color.Purple;");
+    assert_eq!(err, "Undefined property 'Purple'.\n[line 2]\n");
+}
+
+#[test]
+fn variants_cant_be_assigned() {
+    let err = run_err("enum Color { Red }\nColor.Red = 1;");
+    assert_eq!(err, "Only instances have fields.\n[line 2]\n");
+}
+
+#[test]
+fn duplicate_variants_are_a_parse_error() {
+    let output = run("enum E { A, A }");
+    assert_eq!(output.code, 65);
+    assert!(
+        output.stderr.contains("Duplicate variant in enum."),
+        "{}",
+        output.stderr
+    );
+}
@@ -0,0 +1,149 @@
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use common::*;
+
+// A fresh directory to use as the sandbox root.
+fn root(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("lox-fs-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn run_in(root: &Path, source: &str) -> Output {
+    command("run", &["--fs-root", root.to_str().unwrap()], source, &[])
+}
+
+#[test]
+fn files_inside_the_root_can_be_used() {
+    let dir = root("inside");
+    let output = run_in(
+        &dir,
+        "writeFile(\"a.txt\", \"one\");
+         appendFile(\"a.txt\", \" two\");
+         print readFile(\"a.txt\");
+         print exists(\"a.txt\");
+         print listDir(\".\");
+         removeFile(\"a.txt\");
+         print exists(\"a.txt\");",
+    );
+    assert_eq!(output.code, 0, "{}", output.stderr);
+    assert_eq!(output.stdout, "one two\ntrue\n[\"a.txt\"]\nfalse\n");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn file_access_needs_a_root() {
+    let err = run_err("print readFile(\"a.txt\");");
+    assert_eq!(
+        err,
+        "File access is disabled; run with --fs-root <dir> to allow it.\n[line 1]\n"
+    );
+}
+
+#[test]
+fn parent_directories_cant_escape_the_root() {
+    let dir = root("parent");
+    let output = run_in(
+        &dir,
+        "print \"start\";\nprint readFile(\"sub/../../secret\");",
+    );
+    assert_eq!(output.code, 70);
+    assert_eq!(
+        output.stderr,
+        "Path 'sub/../../secret' is outside the allowed root.\n[line 2]\n"
+    );
+    let output = run_in(&dir, "writeFile(\"/tmp/escaped\", \"x\");");
+    assert_eq!(output.code, 70);
+    assert!(
+        output.stderr.contains("is outside the allowed root."),
+        "{}",
+        output.stderr
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn symlinks_cant_escape_the_root() {
+    let dir = root("symlink");
+    let outside = root("symlink-target");
+    fs::write(outside.join("secret"), "hidden").unwrap();
+    std::os::unix::fs::symlink(&outside, dir.join("link")).unwrap();
+    let output = run_in(&dir, "print readFile(\"link/secret\");");
+    assert_eq!(output.code, 70);
+    assert!(
+        output.stderr.contains("is outside the allowed root."),
+        "{}",
+        output.stderr
+    );
+    let output = run_in(&dir, "writeFile(\"link/new\", \"x\");");
+    assert_eq!(output.code, 70);
+    assert!(!outside.join("new").exists());
+    fs::remove_dir_all(&dir).unwrap();
+    fs::remove_dir_all(&outside).unwrap();
+}
+
+#[test]
+fn io_errors_report_the_call_line() {
+    let dir = root("missing");
+    let output = run_in(&dir, "var x = 1;\nprint readFile(\"missing.txt\");");
+    assert_eq!(output.code, 70);
+    assert!(
+        output.stderr.starts_with("Can't read 'missing.txt': "),
+        "{}",
+        output.stderr
+    );
+    assert!(output.stderr.ends_with("[line 2]\n"), "{}", output.stderr);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn native_argument_errors_report_the_call_line() {
+    let err = run_err("var x = 1;\n\nprint sqrt(\"x\");");
+    assert_eq!(err, "Arguments to sqrt() must be numbers.\n[line 3]\n");
+}
+
+#[test]
+fn try_turns_errors_into_values() {
+    let dir = root("try");
+    let output = run_in(
+        &dir,
+        "var r = try(readFile, [\"missing.txt\"]);
+         print r[\"ok\"];
+         print r[\"line\"];
+         writeFile(\"a.txt\", \"one\");
+         print try(readFile, [\"a.txt\"]);
+         fun f(x) { return x.y; }
+         var e = try(f, [nil]);
+         print e[\"error\"];
+         print e[\"line\"];",
+    );
+    assert_eq!(output.code, 0, "{}", output.stderr);
+    assert_eq!(
+        output.stdout,
+        "false\nnil\n{\"ok\": true, \"value\": \"one\"}\nOnly objects have properties.\n6\n"
+    );
+    let output = run_in(&dir, "print try(readFile, [\"../x\"])[\"error\"];");
+    assert_eq!(output.stdout, "Path '../x' is outside the allowed root.\n");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn try_argument_errors() {
+    let err = run_err("print try(1, []);");
+    assert_eq!(
+        err,
+        "First argument to try() must be a function.\n[line 1]\n"
+    );
+    let err = run_err("print try(readFile, \"a\");");
+    assert_eq!(
+        err,
+        "Second argument to try() must be a list of arguments.\n[line 1]\n"
+    );
+    let err = run_err("print try(readFile, []);");
+    assert_eq!(err, "Expected 1 arguments but got 0.\n");
+}
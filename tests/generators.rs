@@ -0,0 +1,87 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn generator_keeps_state_between_calls() {
+    let out = run_ok(
+        "fun* count(n) { var i = 0; while (i < n) { yield i; i = i + 1; } }
+         var g = count(2);
+         print g.next(); print g.done(); print g.next(); print g.next(); print g.done();",
+    );
+    assert_eq!(out, "0\nfalse\n1\nnil\ntrue\n");
+}
+
+#[test]
+fn send_resumes_with_a_value() {
+    let out = run_ok(
+        "fun* echo() { var x = yield 1; print x; yield 2; }
+         var g = echo(); print g.next(); print g.send(\"a\");",
+    );
+    assert_eq!(out, "1\na\n2\n");
+}
+
+#[test]
+fn errors_surface_at_the_next_call_site() {
+    let err = run_err(
+        "fun* bad() { yield 1; yield nope; }
+         var b = bad();
+         print b.next();
+         print b.next();",
+    );
+    assert!(err.contains("Undefined variable 'nope'."), "{err}");
+}
+
+#[test]
+fn yield_outside_a_generator_is_a_parse_error() {
+    assert_eq!(run("fun f() { yield 1; }").code, 65);
+}
+
+#[test]
+fn parse_prints_every_expression_form() {
+    let out = command("parse", &[], "a = f(x, y).b[0] or [1, {\"k\": 2}]", &[]);
+    assert_eq!(out.code, 0, "{}", out.stderr);
+    assert_eq!(
+        out.stdout,
+        "(= a (or (index (. (call f x y) b) 0.0) (list 1.0 (map k 2.0))))\n"
+    );
+}
+
+#[test]
+fn evaluate_prints_callables() {
+    let out = command("evaluate", &[], "clock", &[]);
+    assert_eq!(out.stdout, "<native fn>\n");
+}
+
+#[test]
+fn resuming_a_running_generator_reports_the_call_line() {
+    let err = run_err(
+        "var g;
+         fun* selfish() {
+           yield 1;
+           g.next();
+         }
+         g = selfish();
+         g.next();
+         g.next();",
+    );
+    assert_eq!(err, "Generator is already running.\n[line 4]\n");
+    let err = run_err(
+        "var g;
+         fun* loops() {
+           yield 1;
+           for (var x in g) {}
+         }
+         g = loops();
+         for (var x in g) {}",
+    );
+    assert_eq!(err, "Generator is already running.\n[line 4]\n");
+}
+
+#[test]
+fn generator_method_argument_errors() {
+    let err = run_err("fun* g() { yield 1; }\nvar x = g();\nx.send();");
+    assert_eq!(err, "Expected 1 arguments but got 0.\n");
+    let err = run_err("fun* g() { yield 1; }\nprint g().nope;");
+    assert_eq!(err, "Undefined property 'nope'.\n[line 2]\n");
+}
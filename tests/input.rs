@@ -0,0 +1,82 @@
+mod common;
+
+use std::cell::RefCell;
+use std::io::Cursor;
+use std::rc::Rc;
+
+use codecrafters_interpreter::interpreter::{Interpreter, RuntimeException};
+use codecrafters_interpreter::native::nil;
+use codecrafters_interpreter::parser::Parser;
+use codecrafters_interpreter::scanner::Scanner;
+use common::*;
+
+fn interpret(interpreter: &mut Interpreter, source: &str) -> Result<(), RuntimeException> {
+    let mut scanner = Scanner::new(source);
+    scanner.scan_tokens();
+    let stmts = Parser::new(scanner.tokens).parse();
+    interpreter.interprete(&stmts)
+}
+
+#[test]
+fn read_line_returns_nil_at_end_of_input() {
+    let output = run_with_input(
+        "var line = readLine();
+         while (line != nil) { print \"got \" + line; line = readLine(); }
+         print readLine();",
+        "one\r\ntwo\nthree",
+    );
+    assert_eq!(output.code, 0, "{}", output.stderr);
+    assert_eq!(output.stdout, "got one\ngot two\ngot three\nnil\n");
+}
+
+#[test]
+fn input_prints_its_prompt() {
+    let output = run_with_input(
+        "var name = input(\"Name: \");\nprint \"Hello, \" + name;\nprint input(\"> \");",
+        "Ada\n",
+    );
+    assert_eq!(output.code, 0, "{}", output.stderr);
+    assert_eq!(output.stdout, "Name: Hello, Ada\n> nil\n");
+}
+
+#[test]
+fn read_all_takes_the_rest() {
+    let output = run_with_input(
+        "print readLine();\nprint readAll();\nprint readAll() == \"\";",
+        "first\nsecond\nthird\n",
+    );
+    assert_eq!(output.stdout, "first\nsecond\nthird\n\ntrue\n");
+}
+
+#[test]
+fn embedders_can_inject_input() {
+    let lines = Rc::new(RefCell::new(Vec::new()));
+    let mut interpreter = Interpreter::new();
+    interpreter.input = Box::new(Cursor::new("a\nb\n"));
+    let sink = lines.clone();
+    interpreter.define_native("record", 1, move |_, arguments| {
+        sink.borrow_mut().push(arguments[0].to_string());
+        Ok(nil())
+    });
+    if let Err(e) = interpret(
+        &mut interpreter,
+        "record(readLine()); record(readLine()); record(readLine());",
+    ) {
+        panic!("{e}");
+    }
+    assert_eq!(*lines.borrow(), ["a", "b", "nil"]);
+}
+
+#[test]
+fn input_errors_are_runtime_errors() {
+    let output = run_with_input("readLine(1);", "");
+    assert_eq!(output.code, 70);
+    assert_eq!(output.stderr, "Expected 0 arguments but got 1.\n");
+    let mut interpreter = Interpreter::new();
+    interpreter.input = Box::new(Cursor::new(vec![0xff, 0xfe, b'\n']));
+    let err = interpret(&mut interpreter, "print readLine();").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Can't read input: stream did not contain valid UTF-8.\n[line 1]"
+    );
+}
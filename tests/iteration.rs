@@ -0,0 +1,69 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn iterates_over_builtin_iterables() {
+    let out = run_ok(
+        "for (var x in [1, 2]) print x;
+         for (var k in {\"b\": 1, \"a\": 2}) print k;
+         for (var c in \"hé\") print c;
+         for (var i in range(0, 2)) print i;",
+    );
+    assert_eq!(out, "1\n2\na\nb\nh\né\n0\n1\n");
+}
+
+#[test]
+fn iterates_over_user_objects_with_the_protocol() {
+    let out = run_ok(
+        "class Countdown {
+           init(n) { this.n = n; }
+           iter() { return this; }
+           next() { if (this.n == 0) return nil; this.n = this.n - 1; return this.n; }
+         }
+         for (var x in Countdown(3)) print x;",
+    );
+    assert_eq!(out, "2\n1\n0\n");
+}
+
+#[test]
+fn each_iteration_gets_a_fresh_binding() {
+    let out = run_ok(
+        "var fs = [nil, nil, nil];
+         for (var i in range(0, 3)) { fun get() { return i; } fs[i] = get; }
+         for (var f in fs) print f();",
+    );
+    assert_eq!(out, "0\n1\n2\n");
+}
+
+#[test]
+fn non_iterables_are_runtime_errors() {
+    let err = run_err("var x = 1;\nfor (var y in x) print y;");
+    assert_eq!(
+        err,
+        "Can only iterate over lists, maps, strings, ranges and iterators.\n[line 2]\n"
+    );
+}
+
+#[test]
+fn index_errors() {
+    let err = run_err("print [1][5];");
+    assert_eq!(err, "List index out of range.\n[line 1]\n");
+    let err = run_err("var l = [1];\nl[1.5] = 2;");
+    assert_eq!(err, "List index must be an integer.\n[line 2]\n");
+    let err = run_err("print 1[0];");
+    assert_eq!(err, "Only lists and maps can be indexed.\n[line 1]\n");
+    let err = run_err("print {1: 2};");
+    assert_eq!(err, "Map keys must be strings.\n[line 1]\n");
+    assert_eq!(run_ok("var m = {};\nprint m[\"x\"];"), "nil\n");
+}
+
+// Printing a list or map that contains itself used to overflow the stack.
+#[test]
+fn cyclic_containers_print_without_recursing() {
+    let out = run_ok(
+        "var l = [1, nil]; l[1] = l; print l;
+         var m = {}; m[\"self\"] = m; print m;",
+    );
+    assert_eq!(out, "[1, [...]]\n{\"self\": {...}}\n");
+}
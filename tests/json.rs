@@ -0,0 +1,104 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn values_round_trip() {
+    let out = run_ok(
+        "var v = {\"a\": [1, 2.5, true, nil, \"text\"], \"b\": {}};
+         var s = jsonStringify(v, nil);
+         print s;
+         print jsonStringify(jsonParse(s), nil) == s;",
+    );
+    assert_eq!(out, "{\"a\":[1,2.5,true,null,\"text\"],\"b\":{}}\ntrue\n");
+}
+
+#[test]
+fn stringify_indents() {
+    let out = run_ok("print jsonStringify({\"a\": [1, 2], \"b\": {}}, 2);");
+    assert_eq!(out, "{\n  \"a\": [\n    1,\n    2\n  ],\n  \"b\": {}\n}\n");
+}
+
+#[test]
+fn parse_handles_escapes() {
+    let output = run_with_input(
+        "var v = jsonParse(readAll());\nprint v[0].len();\nprint v[1];\nprint jsonStringify(v, nil);",
+        r#"["a\nb", "\u00e9\ud83d\ude00", "q\"\\"]"#,
+    );
+    assert_eq!(output.code, 0, "{}", output.stderr);
+    assert_eq!(output.stdout, "3\né😀\n[\"a\\nb\",\"é😀\",\"q\\\"\\\\\"]\n");
+}
+
+#[test]
+fn parse_errors_report_positions() {
+    let err = run_err("var x = 1;\nprint jsonParse(\"[1, 2\");");
+    assert_eq!(
+        err,
+        "Invalid JSON at line 1, column 6: expected ',' or ']' in array.\n[line 2]\n"
+    );
+    let err = run_err("print jsonParse(\"{}  x\");");
+    assert!(
+        err.starts_with("Invalid JSON at line 1, column 5: unexpected text after the JSON value."),
+        "{err}"
+    );
+}
+
+#[test]
+fn deep_documents_are_rejected() {
+    let err = run_err(&format!("print jsonParse(\"{}\");", "[".repeat(600)));
+    assert!(err.contains("nesting is too deep."), "{err}");
+}
+
+#[test]
+fn deeply_nested_values_are_rejected_not_overflowed() {
+    let err = run_err(
+        "var x = [];
+         for (var i in range(0, 100000)) x = [x];
+         print jsonStringify(x, nil);",
+    );
+    assert!(
+        err.starts_with("Can't convert values nested more than 512 deep to JSON."),
+        "{err}"
+    );
+}
+
+#[test]
+fn nesting_up_to_the_limit_round_trips() {
+    let out = run_ok(
+        "var x = 1;
+         for (var i in range(0, 512)) x = [x];
+         var s = jsonStringify(x, nil);
+         print jsonStringify(jsonParse(s), nil) == s;",
+    );
+    assert_eq!(out, "true\n");
+}
+
+#[test]
+fn unconvertible_values_are_errors() {
+    let err = run_err("var l = [];\nl.push(l);\nprint jsonStringify(l, nil);");
+    assert_eq!(err, "Can't convert a cyclic structure to JSON.\n[line 3]\n");
+    let err = run_err("print jsonStringify(NAN, nil);");
+    assert!(
+        err.starts_with("Can't convert NaN or infinity to JSON."),
+        "{err}"
+    );
+    let err = run_err("print jsonStringify(clock, nil);");
+    assert!(
+        err.starts_with("Can't convert <native fn> to JSON."),
+        "{err}"
+    );
+}
+
+#[test]
+fn try_recovers_from_invalid_json() {
+    let out = run_ok(
+        "var r = try(jsonParse, [\"[1, 2\"]);
+         print r[\"ok\"];
+         print r[\"error\"];
+         print try(jsonParse, [\"[1, 2]\"])[\"value\"];",
+    );
+    assert_eq!(
+        out,
+        "false\nInvalid JSON at line 1, column 6: expected ',' or ']' in array.\n[1, 2]\n"
+    );
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn unary_functions() {
+    let out = run_ok(
+        "print sqrt(16);
+         print abs(-3);
+         print floor(2.7);
+         print ceil(2.1);
+         print round(2.5);
+         print log(E);
+         print exp(0);
+         print sin(0);
+         print cos(0);",
+    );
+    assert_eq!(out, "4\n3\n2\n3\n3\n1\n1\n0\n1\n");
+}
+
+#[test]
+fn binary_functions() {
+    let out = run_ok(
+        "print pow(2, 10);
+         print min(3, 1);
+         print max(3, 1);
+         print hypot(3, 4);
+         print atan2(0, 1);",
+    );
+    assert_eq!(out, "1024\n1\n3\n5\n0\n");
+}
+
+#[test]
+fn constants() {
+    let out = run_ok("print PI;\nprint INF;\nprint -INF;\nprint NAN;\nprint NAN == NAN;");
+    assert_eq!(out, "3.141592653589793\ninf\n-inf\nNaN\nfalse\n");
+}
+
+#[test]
+fn domain_errors_give_nan() {
+    assert_eq!(run_ok("print sqrt(-1);"), "NaN\n");
+}
+
+#[test]
+fn wrong_argument_type_is_an_error() {
+    let err = run_err("print sqrt(\"x\");");
+    assert!(
+        err.starts_with("Arguments to sqrt() must be numbers."),
+        "{err}"
+    );
+    let err = run_err("print max(1, nil);");
+    assert!(
+        err.starts_with("Arguments to max() must be numbers."),
+        "{err}"
+    );
+}
+
+#[test]
+fn wrong_arity_is_an_error() {
+    let err = run_err("print pow(2);");
+    assert!(err.starts_with("Expected 2 arguments but got 1."), "{err}");
+}
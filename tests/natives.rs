@@ -0,0 +1,59 @@
+mod common;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use codecrafters_interpreter::interpreter::{Interpreter, RuntimeException};
+use codecrafters_interpreter::native::{nil, number, number_arg};
+use codecrafters_interpreter::parser::{Parser, ParserStatus};
+use codecrafters_interpreter::scanner::Scanner;
+use common::*;
+
+fn interpret(interpreter: &mut Interpreter, source: &str) -> Result<(), RuntimeException> {
+    let mut scanner = Scanner::new(source);
+    scanner.scan_tokens();
+    let mut parser = Parser::new(scanner.tokens);
+    let stmts = parser.parse();
+    assert!(matches!(parser.status, ParserStatus::Success));
+    interpreter.interprete(&stmts)
+}
+
+#[test]
+fn embedders_can_define_natives() {
+    let recorded = Rc::new(RefCell::new(Vec::new()));
+    let mut interpreter = Interpreter::new();
+    interpreter.define_native("double", 1, |_, arguments| {
+        Ok(number(number_arg(&arguments[0], "double")? * 2.0))
+    });
+    let sink = recorded.clone();
+    interpreter.define_native("record", 1, move |_, arguments| {
+        sink.borrow_mut().push(arguments[0].to_string());
+        Ok(nil())
+    });
+    if let Err(e) = interpret(&mut interpreter, "record(double(21)); record(double);") {
+        panic!("{e}");
+    }
+    assert_eq!(*recorded.borrow(), ["42", "<native fn>"]);
+}
+
+#[test]
+fn native_arity_is_checked() {
+    let mut interpreter = Interpreter::new();
+    interpreter.define_native("one", 1, |_, arguments| Ok(arguments[0].clone()));
+    let err = interpret(&mut interpreter, "one(1, 2);").unwrap_err();
+    assert_eq!(err.to_string(), "Expected 1 arguments but got 2.");
+}
+
+#[test]
+fn native_argument_errors_are_runtime_errors() {
+    let err = run_err("print range(1, \"a\");");
+    assert_eq!(err, "Range bounds must be numbers.\n[line 1]\n");
+}
+
+#[test]
+fn natives_print_as_native_functions() {
+    assert_eq!(
+        run_ok("print clock;\nprint range;"),
+        "<native fn>\n<native fn>\n"
+    );
+}
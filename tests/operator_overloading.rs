@@ -0,0 +1,121 @@
+mod common;
+
+use common::*;
+
+const VECTOR: &str = "class V {
+  init(x, y) { this.x = x; this.y = y; }
+  __add__(o) { return V(this.x + o.x, this.y + o.y); }
+  __eq__(o) { return this.x == o.x and this.y == o.y; }
+  __lt__(o) { return this.x < o.x; }
+  __neg__() { return V(-this.x, -this.y); }
+}
+";
+
+#[test]
+fn arithmetic_calls_dunder_methods() {
+    let out = run_ok(&format!(
+        "{VECTOR}var a = V(1, 2) + V(3, 4);\nprint a.x;\nprint a.y;\nprint (-a).y;"
+    ));
+    assert_eq!(out, "4\n6\n-6\n");
+}
+
+#[test]
+fn equality_uses_eq_and_its_negation() {
+    let out = run_ok(&format!(
+        "{VECTOR}print V(1, 2) == V(1, 2);\nprint V(1, 2) != V(1, 3);"
+    ));
+    assert_eq!(out, "true\ntrue\n");
+}
+
+#[test]
+fn comparisons_fall_back_to_the_mirrored_method() {
+    let out = run_ok(&format!(
+        "{VECTOR}print V(1, 0) < V(2, 0);\nprint V(2, 0) > V(1, 0);"
+    ));
+    assert_eq!(out, "true\ntrue\n");
+}
+
+#[test]
+fn missing_operator_method_is_an_error() {
+    let err = run_err("class A {}\nprint A() + 1;");
+    assert_eq!(
+        err,
+        "Operands must be two numbers or two strings.\n[line 2]\n"
+    );
+}
+
+#[test]
+fn callables_are_truthy() {
+    let out = run_ok("fun f() {}\nif (f) print \"truthy\";\nprint !f;\nprint !clock;");
+    assert_eq!(out, "truthy\nfalse\nfalse\n");
+}
+
+#[test]
+fn callables_compare_by_identity() {
+    let out = run_ok(
+        "fun f() {}
+         fun h() {}
+         class A { m() {} }
+         var g = f;
+         var a = A();
+         print f == f;
+         print g == f;
+         print f == h;
+         print clock == clock;
+         print A == A;
+         print f == 1;
+         print f == nil;
+         print a.m == a.m;",
+    );
+    assert_eq!(out, "true\ntrue\nfalse\ntrue\ntrue\nfalse\nfalse\nfalse\n");
+}
+
+#[test]
+fn comparisons_with_nil_dont_call_eq() {
+    let out = run_ok(&format!(
+        "{VECTOR}var a = V(1, 2);\nprint a == nil;\nprint nil != a;\nprint a == a;"
+    ));
+    assert_eq!(out, "false\ntrue\ntrue\n");
+}
+
+#[test]
+fn contains_uses_eq() {
+    let out = run_ok(&format!(
+        "{VECTOR}var l = [nil, V(1, 2)];\nprint l.contains(V(1, 2));\nprint l.contains(V(2, 2));\nprint l.contains(nil);"
+    ));
+    assert_eq!(out, "true\nfalse\ntrue\n");
+}
+
+#[test]
+fn errors_in_eq_report_their_line() {
+    let err = run_err(
+        "class A { __eq__(o) { return o.missing; } }\nvar l = [A()];\nprint l.contains(A());",
+    );
+    assert_eq!(err, "Undefined property 'missing'.\n[line 1]\n");
+}
+
+#[test]
+fn class_declaration_errors() {
+    let err = run_err("var A = 1;\nclass B < A {}");
+    assert_eq!(err, "Superclass must be a class.\n[line 2]\n");
+    let err = run_err("class A { init(x) {} }\nA();");
+    assert_eq!(err, "Expected 1 arguments but got 0.\n");
+    let err = run_err("var a = 1;\na.x = 2;");
+    assert_eq!(err, "Only instances have fields.\n[line 2]\n");
+    let output = run("class A { init() { return 1; } }");
+    assert_eq!(output.code, 65);
+    assert!(
+        output
+            .stderr
+            .contains("Can't return a value from an initializer."),
+        "{}",
+        output.stderr
+    );
+}
+
+#[test]
+fn parse_prints_property_assignment() {
+    let out = command("parse", &[], "a.b = c.d", &[]);
+    assert_eq!(out.code, 0, "{}", out.stderr);
+    assert_eq!(out.stdout, "(= (. a b) (. c d))\n");
+}
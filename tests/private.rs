@@ -0,0 +1,76 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn methods_use_private_fields_and_methods() {
+    let out = run_ok(
+        "class Account {
+           init(b) { this.#balance = b; }
+           deposit(n) { this.#balance = this.#balance + n; return this.#log(); }
+           #log() { return this.#balance; }
+         }
+         print Account(10).deposit(5);",
+    );
+    assert_eq!(out, "15\n");
+}
+
+#[test]
+fn other_instances_of_the_class_are_accessible() {
+    let out = run_ok(
+        "class A { init() { this.#x = 1; } same(o) { return o.#x; } }
+         print A().same(A());",
+    );
+    assert_eq!(out, "1\n");
+}
+
+#[test]
+fn private_names_outside_a_class_are_a_resolve_error() {
+    for source in [
+        "class A { init() { this.#x = 1; } }\nprint A().#x;",
+        "class A { #m() {} }\nA().#m();",
+    ] {
+        let output = run(source);
+        assert_eq!(output.code, 65);
+        assert!(
+            output.stderr.contains("Error at '#")
+                && output
+                    .stderr
+                    .contains("Can't use a private name outside of a class."),
+            "{}",
+            output.stderr
+        );
+    }
+}
+
+#[test]
+fn other_classes_cant_read_or_write_private_members() {
+    let read = run_err(
+        "class A { init() { this.#x = 1; } }
+class B { peek(o) { return o.#x; } }
+print B().peek(A());",
+    );
+    assert_eq!(
+        read,
+        "Can't access private member '#x' of class 'A'.\n[line 2]\n"
+    );
+    let write = run_err(
+        "class A { init() { this.#x = 1; } }
+class B { poke(o) { o.#x = 2; } }
+B().poke(A());",
+    );
+    assert_eq!(
+        write,
+        "Can't access private member '#x' of class 'A'.\n[line 2]\n"
+    );
+}
+
+#[test]
+fn subclasses_dont_see_private_fields() {
+    let err = run_err(
+        "class A { init() { this.#x = 1; } }
+class B < A { get() { return this.#x; } }
+print B().get();",
+    );
+    assert_eq!(err, "Undefined property '#x'.\n[line 2]\n");
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use common::*;
+
+const DRAWS: &str = "print random();
+print randomInt(1, 100);
+var xs = [1, 2, 3, 4, 5];
+shuffle(xs);
+print xs;
+print choice(xs);";
+
+fn seeded(seed: &str, source: &str) -> Output {
+    command("run", &["--seed", seed], source, &[])
+}
+
+#[test]
+fn same_seed_gives_the_same_sequence() {
+    let first = seeded("42", DRAWS);
+    assert_eq!(first.code, 0, "{}", first.stderr);
+    assert_eq!(first.stdout, seeded("42", DRAWS).stdout);
+    assert_ne!(first.stdout, seeded("43", DRAWS).stdout);
+}
+
+#[test]
+fn random_int_bounds_are_inclusive() {
+    let output = seeded(
+        "7",
+        "var seen = {};
+         for (var i in range(0, 200)) seen[toString(randomInt(1, 3))] = true;
+         print seen.keys();
+         print randomInt(3, 3);",
+    );
+    assert_eq!(output.stdout, "[\"1\", \"2\", \"3\"]\n3\n");
+}
+
+#[test]
+fn shuffle_keeps_the_elements() {
+    let output = seeded(
+        "1",
+        "var xs = [];
+         for (var i in range(0, 50)) xs.push(toString(i));
+         shuffle(xs);
+         var seen = {};
+         for (var x in xs) seen[x] = true;
+         print xs.len();
+         print seen.keys().len();",
+    );
+    assert_eq!(output.stdout, "50\n50\n");
+}
+
+#[test]
+fn argument_errors() {
+    let err = run_err("print choice([]);");
+    assert_eq!(err, "Can't choose from an empty list.\n[line 1]\n");
+    let err = run_err("print randomInt(5, 1);");
+    assert_eq!(
+        err,
+        "randomInt() bounds must be integers with lo <= hi.\n[line 1]\n"
+    );
+    let err = run_err("shuffle(\"abc\");");
+    assert_eq!(err, "Argument to shuffle() must be a list.\n[line 1]\n");
+}
+
+#[test]
+fn bad_seed_is_a_usage_error() {
+    let output = seeded("abc", "print 1;");
+    assert_eq!(output.code, 64);
+    assert!(
+        output
+            .stderr
+            .contains("--seed needs a non-negative integer"),
+        "{}",
+        output.stderr
+    );
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn methods() {
+    let out = run_ok(
+        "var r = regex(\"(?P<word>[a-z]+)([0-9]*)\");
+         print r.test(\"abc1\");
+         print r.test(\"123\");
+         print r.findAll(\"ab1 cd ef3\");
+         print r.replace(\"ab1 cd\", \"<$word>\");
+         print regex(\",[ ]*\").split(\"a, b,c\");
+         print r;
+         print r.test;",
+    );
+    assert_eq!(
+        out,
+        "true\nfalse\n[\"ab1\", \"cd\", \"ef3\"]\n<ab> <cd>\n[\"a\", \"b\", \"c\"]\n<regex (?P<word>[a-z]+)([0-9]*)>\n<native fn>\n"
+    );
+}
+
+#[test]
+fn match_returns_a_map_or_nil() {
+    let out = run_ok(
+        "var r = regex(\"(?P<word>[a-z]+)([0-9]*)\");
+         var m = r.match(\"XX foo42\");
+         print m[\"text\"];
+         print m[\"index\"];
+         print m[\"groups\"];
+         print m[\"named\"];
+         print r.match(\"!!!\");",
+    );
+    assert_eq!(
+        out,
+        "foo42\n3\n[\"foo\", \"42\"]\n{\"word\": \"foo\"}\nnil\n"
+    );
+}
+
+#[test]
+fn compiled_patterns_are_cached() {
+    let out = run_ok(
+        "print regex(\"a+\") == regex(\"a+\");
+         print regex(\"a+\") == regex(\"b+\");
+         for (var i in range(0, 300)) regex(\"x\" + toString(i));
+         print regex(\"a+\").test(\"aa\");",
+    );
+    assert_eq!(out, "true\nfalse\ntrue\n");
+}
+
+#[test]
+fn invalid_patterns_report_the_column() {
+    let err = run_err("var x = 1;\nprint regex(\"a(b\");");
+    assert_eq!(
+        err,
+        "Invalid regex at column 2: unclosed group.\n[line 2]\n"
+    );
+    let err = run_err("print regex(\"ab)\");");
+    assert_eq!(
+        err,
+        "Invalid regex at column 3: unopened group.\n[line 1]\n"
+    );
+}
+
+#[test]
+fn argument_errors() {
+    let err = run_err("print regex(1);");
+    assert_eq!(err, "Argument to regex() must be a string.\n[line 1]\n");
+    let err = run_err("print regex(\"a\").test(1);");
+    assert_eq!(err, "Argument to test() must be a string.\n[line 1]\n");
+    let err = run_err("print regex(\"a\").replace(\"a\");");
+    assert!(err.starts_with("Expected 2 arguments but got 1."), "{err}");
+    let err = run_err("print regex(\"a\").nope;");
+    assert_eq!(err, "Undefined property 'nope'.\n[line 1]\n");
+}
+
+#[test]
+fn try_recovers_from_invalid_patterns() {
+    let out = run_ok(
+        "print try(regex, [\"a(b\"])[\"ok\"];
+         print try(regex, [\"a\"])[\"value\"].test(\"cat\");",
+    );
+    assert_eq!(out, "false\ntrue\n");
+}
@@ -0,0 +1,125 @@
+mod common;
+
+use common::*;
+
+fn run_fake_clock(source: &str) -> Output {
+    command("run", &["--fake-clock"], source, &[])
+}
+
+#[test]
+fn sleeping_tasks_wake_in_time_order() {
+    let output = run_fake_clock(
+        "fun* task(name, ms) { yield sleep(ms); print name; print clock() * 1000; }
+         spawn(task(\"slow\", 300));
+         spawn(task(\"fast\", 100));
+         spawn(task(\"mid\", 200));",
+    );
+    assert_eq!(output.code, 0, "{}", output.stderr);
+    assert_eq!(output.stdout, "fast\n100\nmid\n200\nslow\n300\n");
+}
+
+#[test]
+fn tasks_interleave_at_yields() {
+    let output = run_fake_clock(
+        "fun* a() { print \"a1\"; yield; print \"a2\"; }
+         fun* b() { print \"b1\"; yield; print \"b2\"; }
+         spawn(a);
+         spawn(b);
+         print \"main\";",
+    );
+    assert_eq!(output.code, 0, "{}", output.stderr);
+    assert_eq!(output.stdout, "main\na1\nb1\na2\nb2\n");
+}
+
+#[test]
+fn channels_pass_values_between_tasks() {
+    let output = run_fake_clock(
+        "var ch = channel();
+         fun* producer() {
+           for (var i in range(0, 3)) { yield sleep(10); ch.send(i); }
+           ch.send(nil);
+         }
+         fun* consumer() {
+           var v = yield ch.recv();
+           while (v != nil) { print v; v = yield ch.recv(); }
+           print \"done\";
+         }
+         spawn(consumer);
+         spawn(producer);",
+    );
+    assert_eq!(output.code, 0, "{}", output.stderr);
+    assert_eq!(output.stdout, "0\n1\n2\ndone\n");
+}
+
+#[test]
+fn errors_in_tasks_stop_the_script() {
+    let output = run_fake_clock("fun* t() { yield sleep(5); print nope; }\nspawn(t);");
+    assert_eq!(output.code, 70);
+    assert!(
+        output.stderr.starts_with("Undefined variable 'nope'."),
+        "{}",
+        output.stderr
+    );
+}
+
+#[test]
+fn sleep_outside_a_task_is_an_error() {
+    let err = run_err("sleep(100);");
+    assert!(
+        err.starts_with("sleep() can only be awaited with yield inside a spawned task."),
+        "{err}"
+    );
+}
+
+#[test]
+fn recv_outside_a_task_is_an_error() {
+    let err = run_err("var ch = channel(); ch.send(1); print ch.recv();");
+    assert!(
+        err.starts_with("recv() can only be awaited with yield inside a spawned task."),
+        "{err}"
+    );
+}
+
+#[test]
+fn tasks_left_waiting_on_recv_are_a_deadlock() {
+    let output = run_fake_clock(
+        "var ch = channel();
+         fun* waiter() { var v = yield ch.recv(); print v; }
+         spawn(waiter);
+         spawn(waiter);
+         fun* sender() { yield sleep(5); ch.send(1); }
+         spawn(sender);",
+    );
+    assert_eq!(output.code, 70);
+    assert_eq!(output.stdout, "1\n");
+    assert_eq!(
+        output.stderr,
+        "Deadlock: 1 task(s) waiting on recv() with no task left to send.\n"
+    );
+}
+
+#[test]
+fn buffered_values_dont_block() {
+    let output = run_fake_clock(
+        "var ch = channel();
+         ch.send(1);
+         fun* t() { var v = yield ch.recv(); print v; }
+         spawn(t);",
+    );
+    assert_eq!(output.code, 0, "{}", output.stderr);
+    assert_eq!(output.stdout, "1\n");
+}
+
+#[test]
+fn spawn_argument_errors() {
+    let err = run_err("spawn(1);");
+    assert_eq!(
+        err,
+        "Can only spawn generators and functions without parameters.\n[line 1]\n"
+    );
+    let err = run_err("fun f(x) {}\nspawn(f);");
+    assert_eq!(
+        err,
+        "Can only spawn generators and functions without parameters.\n[line 2]\n"
+    );
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn indices_count_characters() {
+    let out = run_ok(
+        "print \"héllo\".len();
+         print \"héllo\".substr(1, 3);
+         print \"héllo\".indexOf(\"l\");
+         print \"x\".substr(5, 2) == \"\";
+         print \"héllo\".substr(pow(10, 300), 1) == \"\";",
+    );
+    assert_eq!(out, "5\néll\n2\ntrue\ntrue\n");
+}
+
+#[test]
+fn transformations() {
+    let out = run_ok(
+        "print \"Hi\".lower();
+         print [\"a\", \"b\"].join(\", \");
+         print \"ab\".repeat(3);
+         print \"a\".repeat(0) == \"\";
+         print \"a-b\".startsWith(\"a\");
+         print \"a-b\".endsWith(\"b\");",
+    );
+    assert_eq!(out, "hi\na, b\nababab\ntrue\ntrue\ntrue\n");
+}
+
+#[test]
+fn character_codes() {
+    assert_eq!(run_ok("print ord(\"é\");\nprint chr(233);"), "233\né\n");
+    let err = run_err("print ord(\"ab\");");
+    assert!(
+        err.starts_with("Argument to ord() must be a single character."),
+        "{err}"
+    );
+    let err = run_err("print chr(55296);");
+    assert!(
+        err.starts_with("55296 is not a valid character code."),
+        "{err}"
+    );
+}
+
+#[test]
+fn number_conversions() {
+    let out = run_ok(
+        "print parseNumber(\" 4.5 \");
+         print parseNumber(\"abc\");
+         print toString(1.5) + \"!\";",
+    );
+    assert_eq!(out, "4.5\nnil\n1.5!\n");
+}
+
+#[test]
+fn huge_repeat_is_an_error_not_an_abort() {
+    for count in ["pow(10, 300)", "200000000", "pow(2, 62)"] {
+        let err = run_err(&format!("print \"ab\".repeat({count});"));
+        assert!(
+            err.starts_with("repeat() result would be too long."),
+            "{err}"
+        );
+    }
+    assert_eq!(run_ok("print \"\".repeat(pow(10, 300)) == \"\";"), "true\n");
+}
+
+#[test]
+fn negative_repeat_is_an_error() {
+    let err = run_err("print \"abc\".repeat(-1);");
+    assert!(
+        err.starts_with("Argument to repeat() must be a non-negative integer."),
+        "{err}"
+    );
+}
+
+#[test]
+fn string_methods_are_also_global_functions() {
+    let out = run_ok(
+        "var s = \" héllo wörld \";
+         print len(s);
+         print trim(s);
+         print upper(trim(s));
+         print lower(\"ABC\");
+         print substr(\"héllo\", 1, 3);
+         print indexOf(\"héllo\", \"l\");
+         print split(\"a,b,c\", \",\");
+         print join([\"a\", \"b\"], \"-\");
+         print replace(\"aaa\", \"a\", \"b\");
+         print startsWith(\"lox\", \"lo\");
+         print endsWith(\"lox\", \"x\");
+         print repeat(\"ab\", 3);
+         print chars(\"hé\");",
+    );
+    assert_eq!(
+        out,
+        "13\nhéllo wörld\nHÉLLO WÖRLD\nabc\néll\n2\n[\"a\", \"b\", \"c\"]\na-b\nbbb\ntrue\ntrue\nababab\n[\"h\", \"é\"]\n"
+    );
+}
+
+#[test]
+fn global_string_function_errors() {
+    let err = run_err("var x = 1;\nprint upper(1);");
+    assert_eq!(
+        err,
+        "First argument to upper() must be a string.\n[line 2]\n"
+    );
+    let err = run_err("print len([1, 2]);");
+    assert_eq!(err, "First argument to len() must be a string.\n[line 1]\n");
+    let err = run_err("print join(\"ab\", \",\");");
+    assert_eq!(err, "First argument to join() must be a list.\n[line 1]\n");
+    let err = run_err("print split(\"a\", 1);");
+    assert_eq!(err, "Argument to split() must be a string.\n[line 1]\n");
+    let err = run_err("print substr(\"a\");");
+    assert_eq!(err, "Expected 3 arguments but got 1.\n");
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn args_are_the_arguments_after_the_script() {
+    let output = command("run", &[], "print args();", &["a", "b c"]);
+    assert_eq!(output.stdout, "[\"a\", \"b c\"]\n");
+    assert_eq!(run_ok("print args();"), "[]\n");
+}
+
+#[test]
+fn getenv_reads_the_environment() {
+    let path = std::env::var("PATH").unwrap();
+    assert_eq!(run_ok("print getenv(\"PATH\");"), format!("{path}\n"));
+    assert_eq!(run_ok("print getenv(\"LOX_TEST_SURELY_UNSET\");"), "nil\n");
+    let err = run_err("print getenv(1);");
+    assert_eq!(err, "Argument to getenv() must be a string.\n[line 1]\n");
+}
+
+#[test]
+fn exit_sets_the_exit_code() {
+    let output = run("print \"start\";\nexit(3);\nprint \"after\";");
+    assert_eq!(output.code, 3);
+    assert_eq!(output.stdout, "start\n");
+    assert_eq!(output.stderr, "");
+    let output = command("evaluate", &[], "exit(2)", &[]);
+    assert_eq!(output.code, 2);
+}
+
+#[test]
+fn exit_runs_deferred_expressions() {
+    let output = run("fun say(s) { print s; }
+         fun f() { defer say(\"cleanup\"); exit(3); print \"unreached\"; }
+         f();
+         print \"after\";");
+    assert_eq!(output.code, 3);
+    assert_eq!(output.stdout, "cleanup\n");
+}
+
+#[test]
+fn exit_in_a_deferred_expression_wins_over_an_error() {
+    let output = run("fun f() { defer exit(4); print nope; }\nf();");
+    assert_eq!(output.code, 4);
+}
+
+#[test]
+fn exit_from_a_task() {
+    let output = command(
+        "run",
+        &["--fake-clock"],
+        "fun* t() { yield sleep(10); exit(5); }\nspawn(t);",
+        &[],
+    );
+    assert_eq!(output.code, 5);
+}
+
+#[test]
+fn exit_code_must_be_a_byte() {
+    let err = run_err("exit(256);");
+    assert_eq!(
+        err,
+        "exit() code must be an integer between 0 and 255.\n[line 1]\n"
+    );
+}
+
+#[test]
+fn try_does_not_catch_exit() {
+    let output = run("try(exit, [3]);\nprint \"after\";");
+    assert_eq!(output.code, 3);
+    assert_eq!(output.stdout, "");
+}
+
+#[test]
+fn deferred_errors_after_exit_keep_its_status() {
+    let output = run("fun f() { defer nope; exit(5); }\nf();");
+    assert_eq!(output.code, 5);
+    assert_eq!(
+        output.stderr,
+        "Script exited with code 5.\nError in deferred expression: Undefined variable 'nope'.\n"
+    );
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn deep_tail_recursion_runs_in_constant_stack() {
+    let out = run_ok(
+        "fun count(n, acc) { if (n == 0) return acc; return count(n - 1, acc + 1); }
+         print count(200000, 0);",
+    );
+    assert_eq!(out, "200000\n");
+}
+
+#[test]
+fn mutual_tail_recursion() {
+    let out = run_ok(
+        "fun isEven(n) { if (n == 0) return true; return isOdd(n - 1); }
+         fun isOdd(n) { if (n == 0) return false; return isEven(n - 1); }
+         print isEven(100001);",
+    );
+    assert_eq!(out, "false\n");
+}
+
+#[test]
+fn errors_after_tail_calls_keep_their_line() {
+    let err = run_err("fun f(n) { if (n == 0) return nope; return f(n - 1); }\nprint f(10);");
+    assert!(err.starts_with("Undefined variable 'nope'."), "{err}");
+}
+
+#[test]
+fn tail_call_runs_before_the_callers_defers() {
+    let out = run_ok(
+        "fun say(s) { print s; }
+         fun h() { print \"h runs\"; return 1; }
+         fun g() { defer say(\"g cleanup\"); return h(); }
+         print g();",
+    );
+    assert_eq!(out, "h runs\ng cleanup\n1\n");
+}
+
+// A top-level `return f(x);` used to leave a tail call that nothing consumed
+// and reached a todo!() while printing the error.
+#[test]
+fn return_outside_a_function_is_a_parse_error() {
+    let out = run("fun g(n) { return n; }\nreturn g(1);");
+    assert_eq!(out.code, 65);
+    assert!(
+        out.stderr.contains("Can't return from top-level code."),
+        "{}",
+        out.stderr
+    );
+}
@@ -0,0 +1,111 @@
+mod common;
+
+use common::*;
+
+fn run_fake_clock(source: &str) -> Output {
+    command("run", &["--fake-clock"], source, &[])
+}
+
+const LEAP_DAY: &str = "var t = parseTime(\"2024-02-29 13:45:07\", \"%Y-%m-%d %H:%M:%S\");\n";
+
+#[test]
+fn fields_of_a_parsed_time() {
+    let out = run_ok(&format!(
+        "{LEAP_DAY}print t;
+         print t.year;
+         print t.month;
+         print t.day;
+         print t.hour;
+         print t.weekday;
+         print t.dayOfYear;"
+    ));
+    assert_eq!(out, "2024-02-29T13:45:07Z\n2024\n2\n29\n13\n4\n60\n");
+}
+
+#[test]
+fn format_specifiers() {
+    let out = run_ok(&format!(
+        "{LEAP_DAY}print formatTime(t, \"%a %d %b %Y, %A %B %j %%\");"
+    ));
+    assert_eq!(out, "Thu 29 Feb 2024, Thursday February 060 %\n");
+}
+
+#[test]
+fn offsets_change_fields_but_not_the_instant() {
+    let out = run_ok(&format!(
+        "{LEAP_DAY}var local = atOffset(t, 330);
+         print local;
+         print local.hour;
+         print local == t;
+         print parseTime(\"2024-01-01T10:00:00.250+02:00\", \"%Y-%m-%dT%H:%M:%S.%L%z\");"
+    ));
+    assert_eq!(
+        out,
+        "2024-02-29T19:15:07+05:30\n19\ntrue\n2024-01-01T10:00:00.250+02:00\n"
+    );
+}
+
+#[test]
+fn arithmetic_and_comparison() {
+    let out = run_ok(&format!(
+        "{LEAP_DAY}print t + 60;\nprint (t + 86400) - t;\nprint t < t + 1;"
+    ));
+    assert_eq!(out, "2024-02-29T13:46:07Z\n86400\ntrue\n");
+    let err = run_err("print now() + now();");
+    assert_eq!(
+        err,
+        "Operands must be times, or a time and a number of seconds.\n[line 1]\n"
+    );
+}
+
+#[test]
+fn now_follows_the_fake_clock() {
+    let output = run_fake_clock(
+        "print now();
+         fun* later() { yield sleep(1500); print now(); }
+         spawn(later);",
+    );
+    assert_eq!(output.code, 0, "{}", output.stderr);
+    assert_eq!(
+        output.stdout,
+        "1970-01-01T00:00:00Z\n1970-01-01T00:00:01.500Z\n"
+    );
+}
+
+#[test]
+fn parse_errors() {
+    let err = run_err("print parseTime(\"2023-02-29\", \"%Y-%m-%d\");");
+    assert_eq!(
+        err,
+        "Can't parse time '2023-02-29' with format '%Y-%m-%d': date or time out of range.\n[line 1]\n"
+    );
+    let err = run_err("print parseTime(\"2023-01-01x\", \"%Y-%m-%d\");");
+    assert!(err.contains("unexpected text at the end."), "{err}");
+}
+
+#[test]
+fn argument_errors() {
+    let err = run_err("print formatTime(now(), \"%Q\");");
+    assert_eq!(err, "Unknown time format specifier '%Q'.\n[line 1]\n");
+    let err = run_err("print formatTime(1, \"%Y\");");
+    assert_eq!(
+        err,
+        "First argument to formatTime() must be a time.\n[line 1]\n"
+    );
+    let err = run_err("print atOffset(now(), 1440);");
+    assert!(
+        err.starts_with("atOffset() offset must be a whole number of minutes under a day."),
+        "{err}"
+    );
+    let err = run_err("print now().nope;");
+    assert_eq!(err, "Undefined property 'nope'.\n[line 1]\n");
+}
+
+#[test]
+fn out_of_range_times_dont_panic() {
+    let output = run_fake_clock(
+        "print (now() + pow(10, 300)).year > 0;\nprint (now() - pow(10, 20)).year < 0;",
+    );
+    assert_eq!(output.code, 0, "{}", output.stderr);
+    assert_eq!(output.stdout, "true\ntrue\n");
+}
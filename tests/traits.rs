@@ -0,0 +1,62 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn classes_include_trait_methods() {
+    let out = run_ok(
+        "trait Comparable {
+           compare(o);
+           lessThan(o) { return this.compare(o) < 0; }
+         }
+         trait Printable {
+           describe() { return \"item \" + this.name; }
+         }
+         class Base { init(name) { this.name = name; } }
+         class Item < Base with Comparable, Printable {
+           init(name, n) { super.init(name); this.n = n; }
+           compare(o) { return this.n - o.n; }
+         }
+         var a = Item(\"a\", 1);
+         var b = Item(\"b\", 2);
+         print a.lessThan(b);
+         print b.lessThan(a);
+         print a.describe();
+         print Comparable;",
+    );
+    assert_eq!(out, "true\nfalse\nitem a\n<trait Comparable>\n");
+}
+
+#[test]
+fn class_methods_override_trait_methods() {
+    let out = run_ok(
+        "trait T { m() { return 1; } }
+         class A with T { m() { return 2; } }
+         print A().m();",
+    );
+    assert_eq!(out, "2\n");
+}
+
+#[test]
+fn missing_required_method_is_an_error() {
+    let err = run_err("trait T { need(); }\nclass A with T {}");
+    assert_eq!(
+        err,
+        "Class 'A' must implement 'need' required by trait 'T'.\n[line 2]\n"
+    );
+}
+
+#[test]
+fn conflicting_trait_methods_are_an_error() {
+    let err = run_err("trait T { m() {} }\ntrait U { m() {} }\nclass A with T, U {}");
+    assert_eq!(
+        err,
+        "Method 'm' is provided by both 'T' and 'U'.\n[line 3]\n"
+    );
+}
+
+#[test]
+fn only_traits_can_be_included() {
+    let err = run_err("var T = 1;\nclass A with T {}");
+    assert_eq!(err, "Can only include traits.\n[line 2]\n");
+}